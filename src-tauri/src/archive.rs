@@ -0,0 +1,64 @@
+//! Dated archive of every party export, so there's a record of the team at
+//! each point the save was loaded without the user copying anything.
+
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::ExportError;
+use crate::parser::Pokemon;
+
+/// Write `party` as Showdown text and JSON to `<dir>/<YYYY-MM-DD>/<HHMMSS>-<label>`,
+/// returning the path of the text file.
+pub fn archive_party(
+    dir: &Path,
+    now: DateTime<Local>,
+    label: &str,
+    party: &[Pokemon],
+) -> Result<PathBuf, ExportError> {
+    let day_dir = dir.join(now.format("%Y-%m-%d").to_string());
+    fs::create_dir_all(&day_dir).map_err(|e| ExportError::CreateDir(e.to_string()))?;
+    let stem = format!("{}-{}", now.format("%H%M%S"), label);
+
+    let text = party
+        .iter()
+        .map(|mon| mon.display_text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let json =
+        serde_json::to_string_pretty(party).map_err(|e| ExportError::Serialize(e.to_string()))?;
+
+    let text_path = day_dir.join(format!("{}.txt", stem));
+    fs::write(&text_path, text).map_err(|e| ExportError::Write(e.to_string()))?;
+    fs::write(day_dir.join(format!("{}.json", stem)), json)
+        .map_err(|e| ExportError::Write(e.to_string()))?;
+    Ok(text_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::profile::Game;
+    use crate::savegen;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_archive_party() {
+        let sections = parser::active_slot(&savegen::demo_save().unwrap()).unwrap();
+        let party = parser::parse_party(&sections, Game::RadicalRed.profile(), false).unwrap();
+        let dir = std::env::temp_dir().join(format!("rr-archive-{}", std::process::id()));
+        let now = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 7).unwrap();
+
+        let text_path = archive_party(&dir, now, "party", &party).unwrap();
+        let text = fs::read_to_string(&text_path).unwrap();
+        let json = fs::read_to_string(dir.join("2024-03-09").join("140507-party.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(text_path, dir.join("2024-03-09").join("140507-party.txt"));
+        assert_eq!(text.split("\n\n").count(), party.len());
+        assert!(text.starts_with(&party[0].display_text));
+        let archived: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(archived.as_array().unwrap().len(), party.len());
+    }
+}
@@ -0,0 +1,169 @@
+//! The bag: every item pocket and what's in it.
+
+use serde::Serialize;
+
+use crate::data;
+use crate::error::ParseError;
+use crate::parser::{self, u16_le, Section, SECTION_SIZE};
+use crate::profile::{GameProfile, PocketStore};
+
+/// Bytes per item slot: item ID (u16), quantity (u16).
+const ITEM_SLOT_SIZE: usize = 4;
+/// The PC's item storage is near the start of SaveBlock1 in every game.
+const PC_ITEMS_SECTION: u16 = 1;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Pocket {
+    Items,
+    KeyItems,
+    Balls,
+    TmsHms,
+    Berries,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct BagItem {
+    pub id: u16,
+    pub name: &'static str,
+    pub quantity: u16,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BagPocket {
+    pub pocket: Pocket,
+    pub items: Vec<BagItem>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Inventory {
+    pub pockets: Vec<BagPocket>,
+    /// Items deposited in the PC's item storage.
+    pub pc_items: Vec<BagItem>,
+}
+
+/// Read up to `capacity` item slots from `section` at `offset`, stopping at
+/// the first empty slot since the game keeps item lists packed. Quantities
+/// are XORed with `quantity_key`.
+fn read_items(
+    section: &[u8],
+    offset: usize,
+    capacity: usize,
+    quantity_key: u16,
+) -> Result<Vec<BagItem>, ParseError> {
+    Ok((0..capacity)
+        .map(|i| offset + i * ITEM_SLOT_SIZE)
+        .take_while(|&off| off + ITEM_SLOT_SIZE <= section.len())
+        .map(|off| {
+            (
+                u16_le(section, off),
+                u16_le(section, off + 2) ^ quantity_key,
+            )
+        })
+        .take_while(|&(id, _)| id != 0)
+        .map(|(id, quantity)| BagItem {
+            id,
+            name: data::item_name(id),
+            quantity,
+        })
+        .collect())
+}
+
+/// The bag's pockets and the PC's item storage. `raw` is the whole save, for
+/// pockets kept outside the save slots.
+pub fn parse_bag(
+    raw: &[u8],
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<Inventory, ParseError> {
+    let sec0 = parser::find_section(sections, profile.trainer_section)?;
+    // Bag quantities use the low half of the same key as money
+    let quantity_key = parser::read_u32(sec0, profile.security_key_offset)? as u16;
+    let pockets = profile
+        .bag_pockets
+        .iter()
+        .map(|layout| {
+            let store = match layout.store {
+                PocketStore::Section(id) => parser::find_section(sections, id)?,
+                PocketStore::FlashSector(sector) => {
+                    parser::bytes_at(raw, sector * SECTION_SIZE, SECTION_SIZE)?
+                }
+            };
+            Ok(BagPocket {
+                pocket: layout.pocket,
+                items: read_items(store, layout.offset, layout.capacity, quantity_key)?,
+            })
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    // The PC's quantities aren't encrypted
+    let pc_items = read_items(
+        parser::find_section(sections, PC_ITEMS_SECTION)?,
+        profile.pc_items_offset,
+        profile.pc_items_capacity,
+        0,
+    )?;
+    Ok(Inventory { pockets, pc_items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::{self, IoMode};
+
+    #[test]
+    fn test_parse_bag() {
+        let raw = reader::read_save(TEST_SAV, IoMode::Direct).expect("Failed to read save");
+        let sections = parser::active_slot(&raw).expect("Failed to load save");
+        let inventory = parse_bag(&raw, &sections, &RADICAL_RED).expect("Failed to parse bag");
+        let bag = &inventory.pockets;
+        let pockets: Vec<_> = bag.iter().map(|p| (p.pocket, p.items.len())).collect();
+        assert_eq!(
+            pockets,
+            [
+                (Pocket::Items, 16),
+                (Pocket::KeyItems, 8),
+                (Pocket::Balls, 3),
+                (Pocket::TmsHms, 14),
+                (Pocket::Berries, 4),
+            ]
+        );
+        assert_eq!(
+            bag[0].items[..2],
+            [
+                BagItem {
+                    id: 68,
+                    name: "Rare Candy",
+                    quantity: 580
+                },
+                BagItem {
+                    id: 13,
+                    name: "Potion",
+                    quantity: 98
+                },
+            ]
+        );
+        let names = |pocket: &BagPocket| pocket.items.iter().map(|i| i.name).collect::<Vec<_>>();
+        assert_eq!(names(&bag[1])[..3], ["Berry Pouch", "Old Rod", "Teachy TV"]);
+        assert_eq!(
+            bag[2].items[0],
+            BagItem {
+                id: 4,
+                name: "Poke Ball",
+                quantity: 192
+            }
+        );
+        assert_eq!(names(&bag[3])[..2], ["TM05", "TM09"]);
+        assert_eq!(names(&bag[4])[..2], ["Oran Berry", "Pomeg Berry"]);
+
+        assert_eq!(
+            inventory.pc_items,
+            [BagItem {
+                id: 13,
+                name: "Potion",
+                quantity: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Batch operations over Pokemon picked by `Location`, so a multi-select in the
+//! frontend costs one command instead of one per Pokemon. Nothing is written
+//! back to the save; releases and moves are only previewed.
+
+use serde::Serialize;
+
+use crate::error::QueryError;
+use crate::parser::{Location, PcBox, Pokemon};
+use crate::profile::GameProfile;
+
+/// Why releasing a Pokemon might be a mistake.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReleaseWarning {
+    /// The held item, mail included, goes with it.
+    HoldsItem {
+        item: String,
+    },
+    Shiny,
+    Egg,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReleaseCandidate {
+    pub location: Location,
+    pub nickname: String,
+    pub species: String,
+    pub warnings: Vec<ReleaseWarning>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReleasePreview {
+    pub candidates: Vec<ReleaseCandidate>,
+    /// The selection takes every party member that can battle, which the
+    /// game doesn't allow.
+    pub empties_party: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct PlannedMove {
+    pub from: Location,
+    pub to: Location,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MovePreview {
+    pub moves: Vec<PlannedMove>,
+    /// Selected Pokemon that stay put because the box ran out of room.
+    pub unplaced: Vec<Location>,
+    /// See `ReleasePreview::empties_party`.
+    pub empties_party: bool,
+}
+
+/// Look up each of `locations` in the party and boxes, in the order given.
+/// Fails on the first location that holds no Pokemon.
+pub fn select<'a>(
+    party: &'a [Pokemon],
+    boxes: &'a [PcBox],
+    locations: &[Location],
+) -> Result<Vec<&'a Pokemon>, QueryError> {
+    locations
+        .iter()
+        .map(|&location| {
+            party
+                .iter()
+                .chain(boxes.iter().flat_map(|pc_box| pc_box.pokemon.iter()))
+                .find(|mon| mon.location == location)
+                .ok_or(QueryError::NoPokemonAt { location })
+        })
+        .collect()
+}
+
+/// Whether taking `selected` out of the party would leave nobody to battle.
+fn empties_party(party: &[Pokemon], selected: &[&Pokemon]) -> bool {
+    party
+        .iter()
+        .filter(|mon| !mon.is_egg)
+        .all(|mon| selected.iter().any(|s| s.location == mon.location))
+}
+
+/// Showdown text for the selected Pokemon, one set per Pokemon.
+pub fn export_selected(
+    party: &[Pokemon],
+    boxes: &[PcBox],
+    locations: &[Location],
+) -> Result<String, QueryError> {
+    let selected = select(party, boxes, locations)?;
+    Ok(selected
+        .iter()
+        .map(|mon| mon.display_text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+/// Boxes as a Showdown teambuilder import: one team per box, each under a
+/// `=== [folder] Box N ===` header. `box_number` picks a single box; without
+/// it every box that holds a Pokemon is exported.
+pub fn export_boxes(
+    boxes: &[PcBox],
+    box_number: Option<usize>,
+    folder: &str,
+    profile: &GameProfile,
+) -> Result<String, QueryError> {
+    if let Some(box_number) = box_number {
+        if !(1..=profile.box_count).contains(&box_number) {
+            return Err(QueryError::NoSuchBox { box_number });
+        }
+    }
+    let teams: Vec<String> = match box_number {
+        // An empty box is left out of `boxes`, but was asked for by number
+        Some(number) => vec![(number, boxes.iter().find(|b| b.number == number))],
+        None => boxes.iter().map(|b| (b.number, Some(b))).collect(),
+    }
+    .into_iter()
+    .map(|(number, pc_box)| {
+        let mut team = format!("=== [{}] Box {} ===\n\n", folder, number);
+        for mon in pc_box.iter().flat_map(|b| &b.pokemon) {
+            team.push_str(&mon.display_text);
+            team.push_str("\n\n");
+        }
+        team
+    })
+    .collect();
+    Ok(teams.join("\n"))
+}
+
+/// What releasing the selected Pokemon would lose.
+pub fn preview_release(
+    party: &[Pokemon],
+    boxes: &[PcBox],
+    locations: &[Location],
+) -> Result<ReleasePreview, QueryError> {
+    let selected = select(party, boxes, locations)?;
+    let candidates = selected
+        .iter()
+        .map(|mon| {
+            let mut warnings = Vec::new();
+            if let Some(item) = &mon.item {
+                warnings.push(ReleaseWarning::HoldsItem { item: item.clone() });
+            }
+            if mon.is_shiny {
+                warnings.push(ReleaseWarning::Shiny);
+            }
+            if mon.is_egg {
+                warnings.push(ReleaseWarning::Egg);
+            }
+            ReleaseCandidate {
+                location: mon.location,
+                nickname: mon.nickname.clone(),
+                species: mon.species.clone(),
+                warnings,
+            }
+        })
+        .collect();
+    Ok(ReleasePreview {
+        candidates,
+        empties_party: empties_party(party, &selected),
+    })
+}
+
+/// Where the selected Pokemon would land if moved to box `box_number`,
+/// filling its empty slots in order. Pokemon already in that box stay put.
+pub fn preview_move(
+    party: &[Pokemon],
+    boxes: &[PcBox],
+    locations: &[Location],
+    box_number: usize,
+    profile: &GameProfile,
+) -> Result<MovePreview, QueryError> {
+    if !(1..=profile.box_count).contains(&box_number) {
+        return Err(QueryError::NoSuchBox { box_number });
+    }
+    let selected = select(party, boxes, locations)?;
+    let occupied: Vec<usize> = boxes
+        .iter()
+        .filter(|pc_box| pc_box.number == box_number)
+        .flat_map(|pc_box| &pc_box.pokemon)
+        .filter_map(|mon| match mon.location {
+            Location::Box { slot, .. } => Some(slot),
+            _ => None,
+        })
+        .collect();
+    let mut free_slots = (0..profile.box_capacity).filter(|slot| !occupied.contains(slot));
+
+    let mut moves = Vec::new();
+    let mut unplaced = Vec::new();
+    for mon in &selected {
+        if matches!(mon.location, Location::Box { box_number: n, .. } if n == box_number) {
+            continue;
+        }
+        match free_slots.next() {
+            Some(slot) => moves.push(PlannedMove {
+                from: mon.location,
+                to: Location::Box { box_number, slot },
+            }),
+            None => unplaced.push(mon.location),
+        }
+    }
+    Ok(MovePreview {
+        moves,
+        unplaced,
+        empties_party: empties_party(party, &selected),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_export_selected() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let party = parser::parse_party(&sections, &RADICAL_RED, false).expect("Party");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+
+        let locations = [
+            Location::Box {
+                box_number: 1,
+                slot: 1,
+            },
+            Location::Party { slot: 3 },
+        ];
+        let text = export_selected(&party, &boxes, &locations).expect("Both slots are filled");
+        assert!(text.starts_with(&boxes[0].pokemon[1].display_text));
+        assert!(text.ends_with(&party[3].display_text));
+
+        let empty = [Location::Box {
+            box_number: 2,
+            slot: 0,
+        }];
+        assert!(export_selected(&party, &boxes, &empty).is_err());
+    }
+
+    #[test]
+    fn test_export_boxes() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+
+        let text = export_boxes(&boxes, None, "rr", &RADICAL_RED).expect("All boxes");
+        assert!(text.starts_with("=== [rr] Box 1 ===\n\n"));
+        assert_eq!(text.matches("=== [rr] Box ").count(), boxes.len());
+        for mon in boxes.iter().flat_map(|b| &b.pokemon) {
+            assert!(text.contains(&mon.display_text));
+        }
+
+        let box_1 = export_boxes(&boxes, Some(1), "rr", &RADICAL_RED).expect("Box 1 exists");
+        assert_eq!(box_1.matches("=== ").count(), 1);
+        assert!(box_1.contains(&boxes[0].pokemon[0].display_text));
+        assert!(text.starts_with(&box_1));
+
+        let empty_box = RADICAL_RED.box_count;
+        assert!(boxes.iter().all(|b| b.number != empty_box));
+        assert_eq!(
+            export_boxes(&boxes, Some(empty_box), "rr", &RADICAL_RED),
+            Ok(format!("=== [rr] Box {} ===\n\n", empty_box))
+        );
+        assert_eq!(
+            export_boxes(&boxes, Some(0), "rr", &RADICAL_RED),
+            Err(QueryError::NoSuchBox { box_number: 0 })
+        );
+    }
+
+    #[test]
+    fn test_preview_release() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let party = parser::parse_party(&sections, &RADICAL_RED, false).expect("Party");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+
+        let cubchoo = Location::Box {
+            box_number: 1,
+            slot: 0,
+        };
+        let preview = preview_release(&party, &boxes, &[cubchoo, Location::Party { slot: 1 }])
+            .expect("Both slots are filled");
+        assert_eq!(preview.candidates[0].species, "Cubchoo");
+        assert_eq!(
+            preview.candidates[0].warnings,
+            [ReleaseWarning::HoldsItem {
+                item: "Aspear Berry".to_string()
+            }]
+        );
+        assert!(preview.candidates[1].warnings.is_empty());
+        assert!(!preview.empties_party);
+
+        let whole_party: Vec<_> = party.iter().map(|mon| mon.location).collect();
+        let preview = preview_release(&party, &boxes, &whole_party).expect("Party is full");
+        assert!(preview.empties_party);
+    }
+
+    #[test]
+    fn test_preview_move() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let party = parser::parse_party(&sections, &RADICAL_RED, false).expect("Party");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+        // Box 1 holds Pokemon in slots 0-2, 6-8 and 12-13
+        let gyarados = Location::Box {
+            box_number: 1,
+            slot: 1,
+        };
+        let locations = [
+            Location::Party { slot: 3 },
+            gyarados,
+            Location::Party { slot: 4 },
+        ];
+        let to_box = |box_number, slot| Location::Box { box_number, slot };
+
+        let preview =
+            preview_move(&party, &boxes, &locations, 1, &RADICAL_RED).expect("Box 1 exists");
+        assert_eq!(
+            preview.moves,
+            [
+                PlannedMove {
+                    from: Location::Party { slot: 3 },
+                    to: to_box(1, 3),
+                },
+                PlannedMove {
+                    from: Location::Party { slot: 4 },
+                    to: to_box(1, 4),
+                },
+            ]
+        );
+        assert!(preview.unplaced.is_empty());
+        assert!(!preview.empties_party);
+
+        let preview =
+            preview_move(&party, &boxes, &locations, 2, &RADICAL_RED).expect("Box 2 exists");
+        let targets: Vec<_> = preview.moves.iter().map(|m| m.to).collect();
+        assert_eq!(targets, [to_box(2, 0), to_box(2, 1), to_box(2, 2)]);
+
+        let small = GameProfile {
+            box_capacity: 4,
+            ..RADICAL_RED
+        };
+        let preview = preview_move(&party, &boxes, &locations, 1, &small).expect("Box 1 exists");
+        assert_eq!(preview.moves.len(), 1);
+        assert_eq!(preview.unplaced, [Location::Party { slot: 4 }]);
+
+        for box_number in [0, RADICAL_RED.box_count + 1] {
+            assert_eq!(
+                preview_move(&party, &boxes, &locations, box_number, &RADICAL_RED),
+                Err(QueryError::NoSuchBox { box_number })
+            );
+        }
+    }
+}
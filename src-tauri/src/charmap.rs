@@ -1,87 +1,363 @@
-/// Decode a Gen 3 encoded byte slice into a String.
-/// 0xFF is the string terminator.
-pub fn decode_gen3_string(bytes: &[u8]) -> String {
-    let mut result = String::new();
-    for &b in bytes {
-        if b == 0xFF {
-            break;
+//! Text encoding of the Gen 3 games. The Japanese releases use their own
+//! table; every other language shares the international one.
+
+use crate::error::EncodeError;
+
+/// Ends every string shorter than its field; whatever follows it is leftover.
+const TERMINATOR: u8 = 0xFF;
+
+/// Language byte (offset 18 of a Pokemon struct) of the Japanese games.
+const LANGUAGE_JAPANESE: u8 = 1;
+
+/// Japanese characters 0x01-0xEF: hiragana, katakana, full-width digits and
+/// punctuation, then full-width Latin letters.
+const JAPANESE: &str = "\
+あいうえおかきくけこさしすせそたちつてとなにぬねのはひふへほまみむめもやゆよらり\
+るれろわをんぁぃぅぇぉゃゅょがぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽっ\
+アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリ\
+ルレロワヲンァィゥェォャュョガギグゲゴザジズゼゾダヂヅデドバビブベボパピプペポッ\
+０１２３４５６７８９！？。ー・‥『』「」♂♀円．×／\
+ＡＢＣＤＥＦＧＨＩＪＫＬＭＮＯＰＱＲＳＴＵＶＷＸＹＺ\
+ａｂｃｄｅｆｇｈｉｊｋｌｍｎｏｐｑｒｓｔｕｖｗｘｙｚ▶";
+
+/// Which character table a string was written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    International,
+    Japanese,
+}
+
+impl Charset {
+    /// The table for a Pokemon whose struct has language byte `language`.
+    pub fn from_language(language: u8) -> Charset {
+        match language {
+            LANGUAGE_JAPANESE => Charset::Japanese,
+            _ => Charset::International,
         }
-        result.push(decode_char(b));
     }
-    result
 }
 
-fn decode_char(b: u8) -> char {
+/// Decode an international Gen 3 encoded byte slice into a String.
+pub fn decode_gen3_string(bytes: &[u8]) -> String {
+    decode_gen3_string_in(bytes, Charset::International)
+}
+
+/// Decode a Gen 3 encoded byte slice written with `charset`, stopping at the
+/// 0xFF terminator. Text control codes are dropped and unmapped bytes become '?'.
+pub fn decode_gen3_string_in(bytes: &[u8], charset: Charset) -> String {
+    let text = bytes.iter().take_while(|&&b| b != TERMINATOR);
+    match charset {
+        Charset::International => text.filter_map(|&b| decode_char(b)).collect(),
+        Charset::Japanese => text.filter_map(|&b| decode_japanese_char(b)).collect(),
+    }
+}
+
+/// The text for one byte, None for control codes (0xFA-0xFE) that only mean
+/// something inside in-game messages.
+fn decode_char(b: u8) -> Option<&'static str> {
     match b {
-        0x00 => ' ',
-        0xA1 => '0',
-        0xA2 => '1',
-        0xA3 => '2',
-        0xA4 => '3',
-        0xA5 => '4',
-        0xA6 => '5',
-        0xA7 => '6',
-        0xA8 => '7',
-        0xA9 => '8',
-        0xAA => '9',
-        0xAB => '!',
-        0xAC => '?',
-        0xAD => '.',
-        0xAE => '-',
-        0xB5 => '♂',
-        0xB6 => '♀',
-        0xBB => 'A',
-        0xBC => 'B',
-        0xBD => 'C',
-        0xBE => 'D',
-        0xBF => 'E',
-        0xC0 => 'F',
-        0xC1 => 'G',
-        0xC2 => 'H',
-        0xC3 => 'I',
-        0xC4 => 'J',
-        0xC5 => 'K',
-        0xC6 => 'L',
-        0xC7 => 'M',
-        0xC8 => 'N',
-        0xC9 => 'O',
-        0xCA => 'P',
-        0xCB => 'Q',
-        0xCC => 'R',
-        0xCD => 'S',
-        0xCE => 'T',
-        0xCF => 'U',
-        0xD0 => 'V',
-        0xD1 => 'W',
-        0xD2 => 'X',
-        0xD3 => 'Y',
-        0xD4 => 'Z',
-        0xD5 => 'a',
-        0xD6 => 'b',
-        0xD7 => 'c',
-        0xD8 => 'd',
-        0xD9 => 'e',
-        0xDA => 'f',
-        0xDB => 'g',
-        0xDC => 'h',
-        0xDD => 'i',
-        0xDE => 'j',
-        0xDF => 'k',
-        0xE0 => 'l',
-        0xE1 => 'm',
-        0xE2 => 'n',
-        0xE3 => 'o',
-        0xE4 => 'p',
-        0xE5 => 'q',
-        0xE6 => 'r',
-        0xE7 => 's',
-        0xE8 => 't',
-        0xE9 => 'u',
-        0xEA => 'v',
-        0xEB => 'w',
-        0xEC => 'x',
-        0xED => 'y',
-        0xEE => 'z',
-        _ => '?',
+        0xFA..=0xFE => None,
+        _ => Some(glyph(b).unwrap_or("?")),
+    }
+}
+
+/// The international glyph for a byte, None where there is none. Some glyphs,
+/// like the PK and MN of "PKMN", have no single character and decode to two.
+fn glyph(b: u8) -> Option<&'static str> {
+    Some(match b {
+        0x00 => " ",
+        0x01 => "À",
+        0x02 => "Á",
+        0x03 => "Â",
+        0x04 => "Ç",
+        0x05 => "È",
+        0x06 => "É",
+        0x07 => "Ê",
+        0x08 => "Ë",
+        0x09 => "Ì",
+        0x0B => "Î",
+        0x0C => "Ï",
+        0x0D => "Ò",
+        0x0E => "Ó",
+        0x0F => "Ô",
+        0x10 => "Œ",
+        0x11 => "Ù",
+        0x12 => "Ú",
+        0x13 => "Û",
+        0x14 => "Ñ",
+        0x15 => "ß",
+        0x16 => "à",
+        0x17 => "á",
+        0x19 => "ç",
+        0x1A => "è",
+        0x1B => "é",
+        0x1C => "ê",
+        0x1D => "ë",
+        0x1E => "ì",
+        0x20 => "î",
+        0x21 => "ï",
+        0x22 => "ò",
+        0x23 => "ó",
+        0x24 => "ô",
+        0x25 => "œ",
+        0x26 => "ù",
+        0x27 => "ú",
+        0x28 => "û",
+        0x29 => "ñ",
+        0x2A => "º",
+        0x2B => "ª",
+        0x2C => "ᵉʳ",
+        0x2D => "&",
+        0x2E => "+",
+        0x34 => "Lv",
+        0x35 => "=",
+        0x36 => ";",
+        0x51 => "¿",
+        0x52 => "¡",
+        0x53 => "PK",
+        0x54 => "MN",
+        0x55 => "PO",
+        0x56 => "Ké",
+        0x5A => "Í",
+        0x5B => "%",
+        0x5C => "(",
+        0x5D => ")",
+        0x68 => "â",
+        0x6F => "í",
+        0x84 => "ᵉ",
+        0x85 => "<",
+        0x86 => ">",
+        0xA0 => "ʳᵉ",
+        0xA1 => "0",
+        0xA2 => "1",
+        0xA3 => "2",
+        0xA4 => "3",
+        0xA5 => "4",
+        0xA6 => "5",
+        0xA7 => "6",
+        0xA8 => "7",
+        0xA9 => "8",
+        0xAA => "9",
+        0xAB => "!",
+        0xAC => "?",
+        0xAD => ".",
+        0xAE => "-",
+        0xAF => "·",
+        0xB0 => "…",
+        0xB1 => "“",
+        0xB2 => "”",
+        0xB3 => "‘",
+        0xB4 => "’",
+        0xB5 => "♂",
+        0xB6 => "♀",
+        0xB7 => "$",
+        0xB8 => ",",
+        0xB9 => "×",
+        0xBA => "/",
+        0xBB => "A",
+        0xBC => "B",
+        0xBD => "C",
+        0xBE => "D",
+        0xBF => "E",
+        0xC0 => "F",
+        0xC1 => "G",
+        0xC2 => "H",
+        0xC3 => "I",
+        0xC4 => "J",
+        0xC5 => "K",
+        0xC6 => "L",
+        0xC7 => "M",
+        0xC8 => "N",
+        0xC9 => "O",
+        0xCA => "P",
+        0xCB => "Q",
+        0xCC => "R",
+        0xCD => "S",
+        0xCE => "T",
+        0xCF => "U",
+        0xD0 => "V",
+        0xD1 => "W",
+        0xD2 => "X",
+        0xD3 => "Y",
+        0xD4 => "Z",
+        0xD5 => "a",
+        0xD6 => "b",
+        0xD7 => "c",
+        0xD8 => "d",
+        0xD9 => "e",
+        0xDA => "f",
+        0xDB => "g",
+        0xDC => "h",
+        0xDD => "i",
+        0xDE => "j",
+        0xDF => "k",
+        0xE0 => "l",
+        0xE1 => "m",
+        0xE2 => "n",
+        0xE3 => "o",
+        0xE4 => "p",
+        0xE5 => "q",
+        0xE6 => "r",
+        0xE7 => "s",
+        0xE8 => "t",
+        0xE9 => "u",
+        0xEA => "v",
+        0xEB => "w",
+        0xEC => "x",
+        0xED => "y",
+        0xEE => "z",
+        0xEF => "▶",
+        0xF0 => ":",
+        0xF1 => "Ä",
+        0xF2 => "Ö",
+        0xF3 => "Ü",
+        0xF4 => "ä",
+        0xF5 => "ö",
+        0xF6 => "ü",
+        _ => return None,
+    })
+}
+
+/// Like `decode_char`, for the Japanese table.
+fn decode_japanese_char(b: u8) -> Option<char> {
+    match b {
+        0xFA..=0xFE => None,
+        _ => Some(japanese_glyph(b).unwrap_or('?')),
+    }
+}
+
+/// Like `glyph`, for the Japanese table. Past the full-width letters it
+/// matches the international table.
+fn japanese_glyph(b: u8) -> Option<char> {
+    match b {
+        0x00 => Some('\u{3000}'),
+        0x01..=0xEF => JAPANESE.chars().nth(b as usize - 1),
+        0xF0 => Some('：'),
+        _ => glyph(b).and_then(|text| text.chars().next()),
+    }
+}
+
+/// Bytes in a Pokemon's nickname field.
+pub const NICKNAME_LEN: usize = 10;
+
+/// Encode an international string into a `len`-byte field, the inverse of
+/// `decode_gen3_string`.
+pub fn encode_gen3_string(text: &str, len: usize) -> Result<Vec<u8>, EncodeError> {
+    encode_gen3_string_in(text, len, Charset::International)
+}
+
+/// Encode `text` with `charset` into a `len`-byte field. Shorter text is
+/// terminated and padded with 0xFF; text that fills the field has no
+/// terminator, the way the games store full-length names.
+pub fn encode_gen3_string_in(
+    text: &str,
+    len: usize,
+    charset: Charset,
+) -> Result<Vec<u8>, EncodeError> {
+    let mut bytes = text
+        .chars()
+        .map(|c| encode_char(c, charset).ok_or(EncodeError::Unencodable(c)))
+        .collect::<Result<Vec<u8>, EncodeError>>()?;
+    if bytes.len() > len {
+        return Err(EncodeError::TooLong {
+            text: text.to_string(),
+            len,
+        });
+    }
+    bytes.resize(len, TERMINATOR);
+    Ok(bytes)
+}
+
+/// The byte for a character. Two-character glyphs like PK are never picked,
+/// so "PK" is written as P and K.
+fn encode_char(c: char, charset: Charset) -> Option<u8> {
+    (0..TERMINATOR).find(|&b| match charset {
+        Charset::International => glyph(b).is_some_and(|text| text.chars().eq([c])),
+        Charset::Japanese => japanese_glyph(b) == Some(c),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_gen3_string() {
+        // "Nidoran♀", terminator, then leftover bytes from a longer name
+        let nidoran = [0xC8, 0xDD, 0xD8, 0xE3, 0xE6, 0xD5, 0xE2, 0xB6, 0xFF, 0xCA];
+        assert_eq!(decode_gen3_string(&nidoran), "Nidoran♀");
+        // "Flabébé…"
+        let flabebe = [0xC0, 0xE0, 0xD5, 0xD6, 0x1B, 0xD6, 0x1B, 0xB0];
+        assert_eq!(decode_gen3_string(&flabebe), "Flabébé…");
+        assert_eq!(
+            decode_gen3_string(&[0x53, 0x54, 0xFE, 0xCE, 0xCF]),
+            "PKMNTU"
+        );
+        assert_eq!(decode_gen3_string(&[0xFF, 0xBB]), "");
+        assert_eq!(decode_gen3_string(&[0x30]), "?");
+    }
+
+    #[test]
+    fn test_decode_japanese_string() {
+        // "ピカチュウ", padded with terminators to the 10-byte nickname field
+        let pikachu = [0x9C, 0x56, 0x61, 0x85, 0x53, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(Charset::from_language(1), Charset::Japanese);
+        assert_eq!(
+            decode_gen3_string_in(&pikachu, Charset::Japanese),
+            "ピカチュウ"
+        );
+        assert_eq!(
+            decode_gen3_string_in(&[0xBB, 0xA2, 0xF6], Charset::Japanese),
+            "Ａ１ü"
+        );
+        assert_eq!(Charset::from_language(2), Charset::International);
+    }
+
+    #[test]
+    fn test_encode_gen3_string() {
+        let encoded = encode_gen3_string("Flabébé", 10).expect("Name should encode");
+        assert_eq!(encoded.len(), 10);
+        assert_eq!(encoded[7..], [0xFF; 3]);
+        assert_eq!(decode_gen3_string(&encoded), "Flabébé");
+
+        // A full-length name has no terminator
+        let full = encode_gen3_string("Nidoran♀", 8).expect("Name should encode");
+        assert_eq!(full, [0xC8, 0xDD, 0xD8, 0xE3, 0xE6, 0xD5, 0xE2, 0xB6]);
+        assert_eq!(
+            encode_gen3_string("Nidoran♀", 7),
+            Err(EncodeError::TooLong {
+                text: "Nidoran♀".to_string(),
+                len: 7
+            })
+        );
+        assert_eq!(
+            encode_gen3_string("日本", 10),
+            Err(EncodeError::Unencodable('日'))
+        );
+
+        let pikachu =
+            encode_gen3_string_in("ピカチュウ", 10, Charset::Japanese).expect("Name should encode");
+        assert_eq!(
+            decode_gen3_string_in(&pikachu, Charset::Japanese),
+            "ピカチュウ"
+        );
+        assert_eq!(
+            encode_gen3_string_in("Pika", 10, Charset::Japanese),
+            Err(EncodeError::Unencodable('P'))
+        );
+    }
+
+    #[test]
+    fn test_every_glyph_round_trips() {
+        for b in 0..TERMINATOR {
+            if let Some(text) = glyph(b).filter(|text| text.chars().count() == 1) {
+                assert_eq!(encode_gen3_string(text, 1), Ok(vec![b]), "{:#04x}", b);
+            }
+            if let Some(c) = japanese_glyph(b) {
+                let text = c.to_string();
+                let encoded = encode_gen3_string_in(&text, 1, Charset::Japanese);
+                assert_eq!(encoded, Ok(vec![b]), "{:#04x}", b);
+            }
+        }
     }
 }
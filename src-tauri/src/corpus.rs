@@ -0,0 +1,123 @@
+//! Developer tooling: replay the parser over a folder of edge-case saves.
+
+use serde::Serialize;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::error::ParseError;
+use crate::profile::RADICAL_RED;
+use crate::{mail, minigames, parser, rematch};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Ok,
+    Error,
+    Panic,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CorpusEntry {
+    pub file: String,
+    pub outcome: Outcome,
+    pub detail: Option<String>,
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CorpusReport {
+    pub entries: Vec<CorpusEntry>,
+    pub ok: usize,
+    pub errors: usize,
+    pub panics: usize,
+}
+
+/// Run every save parser over the raw bytes, stopping at the first error.
+fn parse_everything(raw: &[u8]) -> Result<(), ParseError> {
+    let sections = parser::active_slot(raw)?;
+    parser::parse_party(&sections, &RADICAL_RED, false)?;
+    parser::parse_pc_boxes(&sections, &RADICAL_RED, false)?;
+    mail::parse_mail(&sections, &RADICAL_RED)?;
+    rematch::parse_rematch_state(&sections, &RADICAL_RED)?;
+    minigames::parse_minigame_records(&sections, &RADICAL_RED)?;
+    Ok(())
+}
+
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+fn replay_file(path: &Path) -> CorpusEntry {
+    let file = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let started = Instant::now();
+
+    let (outcome, detail) = match fs::read(path) {
+        Err(e) => (Outcome::Error, Some(format!("Failed to read file: {}", e))),
+        Ok(raw) => match panic::catch_unwind(AssertUnwindSafe(|| parse_everything(&raw))) {
+            Ok(Ok(())) => (Outcome::Ok, None),
+            Ok(Err(e)) => (Outcome::Error, Some(e.to_string())),
+            Err(payload) => (Outcome::Panic, Some(panic_message(payload))),
+        },
+    };
+
+    CorpusEntry {
+        file,
+        outcome,
+        detail,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+    }
+}
+
+/// Replay every file directly inside `dir`, sorted by name.
+pub fn replay_corpus(dir: &str) -> Result<CorpusReport, ParseError> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| ParseError::Io(e.to_string()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+
+    let mut report = CorpusReport::default();
+    for path in paths {
+        let entry = replay_file(&path);
+        match entry.outcome {
+            Outcome::Ok => report.ok += 1,
+            Outcome::Error => report.errors += 1,
+            Outcome::Panic => report.panics += 1,
+        }
+        report.entries.push(entry);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savegen;
+
+    #[test]
+    fn test_replay_corpus_reports_outcomes() {
+        let dir = std::env::temp_dir().join(format!("rr-corpus-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let demo = savegen::demo_save().unwrap();
+        fs::write(dir.join("a_demo.sav"), &demo).unwrap();
+        fs::write(dir.join("b_truncated.sav"), &demo[..1000]).unwrap();
+        fs::write(dir.join("c_zeroed.sav"), vec![0u8; demo.len()]).unwrap();
+
+        let report = replay_corpus(dir.to_str().unwrap()).expect("Replay failed");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let outcomes: Vec<Outcome> = report.entries.iter().map(|e| e.outcome).collect();
+        assert_eq!(outcomes, [Outcome::Ok, Outcome::Error, Outcome::Error]);
+        assert_eq!((report.ok, report.errors, report.panics), (1, 2, 0));
+    }
+}
@@ -4,6 +4,11 @@ use std::sync::LazyLock;
 const SPECIES_TXT: &str = include_str!("../data/Species.txt");
 const MOVES_TXT: &str = include_str!("../data/Moves.txt");
 const ITEMS_TXT: &str = include_str!("../data/Items.txt");
+const NATIONAL_TXT: &str = include_str!("../data/National.txt");
+const GROWTH_RATES_TXT: &str = include_str!("../data/growth_rates.txt");
+const GENDER_RATIOS_TXT: &str = include_str!("../data/gender_ratios.txt");
+const BASE_STATS_TXT: &str = include_str!("../data/base_stats.txt");
+const LOCATIONS_TXT: &str = include_str!("../data/Locations.txt");
 const ABILITIES_CSV: &str = include_str!("../data/species_abilities.csv");
 
 /// Build a lookup vec from a 1-indexed text file (one name per line).
@@ -23,14 +28,138 @@ pub static MOVES: LazyLock<Vec<&'static str>> = LazyLock::new(|| build_lookup(MO
 /// Item names indexed by item ID. Index 0 = dummy, index 1 = Master Ball, etc.
 pub static ITEMS: LazyLock<Vec<&'static str>> = LazyLock::new(|| build_lookup(ITEMS_TXT));
 
-/// Map from species name (lowercase) to (primary, secondary, hidden) ability names.
+/// Map section names indexed by met location ID, starting from 0 = Littleroot
+/// Town. Kanto and the Sevii Islands start at 0x58 = Pallet Town.
+pub static LOCATIONS: LazyLock<Vec<&'static str>> =
+    LazyLock::new(|| LOCATIONS_TXT.lines().map(|l| l.trim()).collect());
+
+/// National dex numbers keyed by base species name. Bulbasaur = 1.
+pub static NATIONAL_DEX: LazyLock<HashMap<&'static str, u16>> = LazyLock::new(|| {
+    NATIONAL_TXT
+        .lines()
+        .enumerate()
+        .map(|(i, name)| (name.trim(), i as u16 + 1))
+        .collect()
+});
+
+/// Ball names indexed by CFRU ball ID. Unlike vanilla's origins bits, CFRU
+/// counts from 0 = Master Ball and keeps going with the newer balls.
+const BALLS: [&str; 27] = [
+    "Master Ball", "Ultra Ball", "Great Ball", "Poke Ball", "Safari Ball",
+    "Net Ball", "Dive Ball", "Nest Ball", "Repeat Ball", "Timer Ball",
+    "Luxury Ball", "Premier Ball", "Dusk Ball", "Heal Ball", "Quick Ball",
+    "Cherish Ball", "Park Ball", "Fast Ball", "Level Ball", "Lure Ball",
+    "Heavy Ball", "Love Ball", "Friend Ball", "Moon Ball", "Sport Ball",
+    "Beast Ball", "Dream Ball",
+];
+
+/// First national dex number of each generation, Gen 1 first.
+const GENERATION_STARTS: [u16; 9] = [1, 152, 252, 387, 494, 650, 722, 810, 906];
+
+/// Experience curve a species levels up on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrowthRate {
+    Erratic,
+    Fast,
+    MediumFast,
+    MediumSlow,
+    Slow,
+    Fluctuating,
+}
+
+impl GrowthRate {
+    fn from_name(name: &str) -> GrowthRate {
+        match name {
+            "Erratic" => GrowthRate::Erratic,
+            "Fast" => GrowthRate::Fast,
+            "Medium Slow" => GrowthRate::MediumSlow,
+            "Slow" => GrowthRate::Slow,
+            "Fluctuating" => GrowthRate::Fluctuating,
+            _ => GrowthRate::MediumFast,
+        }
+    }
+
+    /// Total experience needed to reach `level`, matching the game's experience tables.
+    pub fn exp_for_level(self, level: u8) -> u32 {
+        let n = level as i64;
+        if n <= 1 {
+            return 0;
+        }
+        let cube = n * n * n;
+        let exp = match self {
+            GrowthRate::Erratic if n <= 50 => (100 - n) * cube / 50,
+            GrowthRate::Erratic if n <= 68 => (150 - n) * cube / 100,
+            GrowthRate::Erratic if n <= 98 => ((1911 - 10 * n) / 3) * cube / 500,
+            GrowthRate::Erratic => (160 - n) * cube / 100,
+            GrowthRate::Fast => 4 * cube / 5,
+            GrowthRate::MediumFast => cube,
+            GrowthRate::MediumSlow => 6 * cube / 5 - 15 * n * n + 100 * n - 140,
+            GrowthRate::Slow => 5 * cube / 4,
+            GrowthRate::Fluctuating if n <= 15 => ((n + 1) / 3 + 24) * cube / 50,
+            GrowthRate::Fluctuating if n <= 36 => (n + 14) * cube / 50,
+            GrowthRate::Fluctuating => (n / 2 + 32) * cube / 50,
+        };
+        exp.max(0) as u32
+    }
+
+    /// Highest level (1-100) whose experience requirement `exp` meets.
+    pub fn level_for_exp(self, exp: u32) -> u8 {
+        (2..=100)
+            .take_while(|&level| self.exp_for_level(level) <= exp)
+            .last()
+            .unwrap_or(1)
+    }
+}
+
+/// Growth rates indexed by national dex number. Index 0 = dummy.
+pub static GROWTH_RATES: LazyLock<Vec<GrowthRate>> = LazyLock::new(|| {
+    let mut v = vec![GrowthRate::MediumFast];
+    v.extend(GROWTH_RATES_TXT.lines().map(|l| GrowthRate::from_name(l.trim())));
+    v
+});
+
+/// Gender thresholds indexed by national dex number. Index 0 = dummy.
+/// A Pokemon is female when its PID's low byte is below the threshold;
+/// 0 = always male, 254 = always female, 255 = genderless.
+pub static GENDER_RATIOS: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let mut v = vec![MON_GENDERLESS];
+    v.extend(GENDER_RATIOS_TXT.lines().map(|l| l.trim().parse().unwrap_or(MON_GENDERLESS)));
+    v
+});
+
+pub const MON_MALE: u8 = 0;
+pub const MON_FEMALE: u8 = 254;
+pub const MON_GENDERLESS: u8 = 255;
+
+/// Base stats indexed by national dex number, in Showdown order (HP, Atk, Def,
+/// SpA, SpD, Spe). Index 0 = dummy. These are the mainline values; species RR
+/// rebalanced are not reflected, so only `base_stats` should read them.
+pub static BASE_STATS: LazyLock<Vec<[u8; 6]>> = LazyLock::new(|| {
+    let mut v = vec![[0; 6]];
+    v.extend(BASE_STATS_TXT.lines().map(|l| {
+        let mut stats = [0; 6];
+        for (stat, value) in stats.iter_mut().zip(l.split_whitespace()) {
+            *stat = value.parse().unwrap_or(0);
+        }
+        stats
+    }));
+    v
+});
+
+/// Key for the abilities map: lowercase, with the CSV's curly apostrophes
+/// ("Farfetch’d") matched to the straight ones in Species.txt.
+fn ability_key(species: &str) -> String {
+    species.replace('’', "'").to_lowercase()
+}
+
+/// Map from species name (see `ability_key`) to (primary, secondary, hidden) ability names.
 pub static ABILITIES: LazyLock<HashMap<String, (String, String, String)>> = LazyLock::new(|| {
     let mut map = HashMap::new();
     for line in ABILITIES_CSV.lines().skip(1) {
         let cols: Vec<&str> = line.split(',').collect();
         if cols.len() >= 4 {
             map.insert(
-                cols[0].trim().to_lowercase(),
+                ability_key(cols[0].trim()),
                 (
                     cols[1].trim().to_string(),
                     cols[2].trim().to_string(),
@@ -42,21 +171,105 @@ pub static ABILITIES: LazyLock<HashMap<String, (String, String, String)>> = Lazy
     map
 });
 
+/// Whether `id` has an entry in the species table.
+pub fn is_known_species(id: u16) -> bool {
+    (1..SPECIES.len()).contains(&(id as usize))
+}
+
 pub fn species_name(id: u16) -> &'static str {
     SPECIES.get(id as usize).copied().unwrap_or("???")
 }
 
-pub fn move_name(id: u16) -> &'static str {
-    MOVES.get(id as usize).copied().unwrap_or("???")
+/// Move name for `id`, or None when the table has no name for it.
+pub fn move_name(id: u16) -> Option<&'static str> {
+    MOVES.get(id as usize).copied().filter(|name| !name.is_empty())
 }
 
 pub fn item_name(id: u16) -> &'static str {
     ITEMS.get(id as usize).copied().unwrap_or("???")
 }
 
+/// Name of a met location, including the special IDs for eggs, in-game
+/// trades and event Pokemon.
+pub fn location_name(id: u8) -> Option<&'static str> {
+    match id {
+        0xFD => Some("Egg"),
+        0xFE => Some("In-game trade"),
+        0xFF => Some("Fateful encounter"),
+        _ => LOCATIONS.get(id as usize).copied(),
+    }
+}
+
+pub fn ball_name(id: u8) -> Option<&'static str> {
+    BALLS.get(id as usize).copied()
+}
+
+/// National dex number for a species name. Form suffixes ("-Mega-X", "-Alola",
+/// "-Sevii", ...) are dropped until a base species matches.
+pub fn national_dex(species: &str) -> Option<u16> {
+    let mut name = species;
+    loop {
+        if let Some(&dex) = NATIONAL_DEX.get(name) {
+            return Some(dex);
+        }
+        name = &name[..name.rfind('-')?];
+    }
+}
+
+/// Species name for a national dex number.
+pub fn national_species(dex: u16) -> Option<&'static str> {
+    NATIONAL_TXT.lines().nth((dex as usize).checked_sub(1)?).map(|l| l.trim())
+}
+
+/// Generation a national dex number was introduced in.
+pub fn generation(dex: u16) -> Option<u8> {
+    if dex == 0 || dex as usize > NATIONAL_DEX.len() {
+        return None;
+    }
+    Some(GENERATION_STARTS.iter().filter(|&&start| dex >= start).count() as u8)
+}
+
+/// Growth rate of a species, by its base species.
+pub fn growth_rate(species: &str) -> Option<GrowthRate> {
+    national_dex(species).and_then(|dex| GROWTH_RATES.get(dex as usize).copied())
+}
+
+/// Species whose stats in a Radical Red save have been checked against the
+/// mainline base stats. Any other species may have been rebalanced, and forms
+/// would get their base species' entry.
+const VERIFIED_BASE_STATS: &[&str] = &["Pawmo", "Luxio", "Cetoddle"];
+
+/// Base stats of a species, only where they're known to match the game's.
+pub fn base_stats(species: &str) -> Option<[u8; 6]> {
+    if !VERIFIED_BASE_STATS.contains(&species) {
+        return None;
+    }
+    national_dex(species).and_then(|dex| BASE_STATS.get(dex as usize).copied())
+}
+
+/// Gender threshold of a species. Female-only forms ("Meowstic-F") are
+/// separate species in RR and always female.
+pub fn gender_ratio(species: &str) -> Option<u8> {
+    if species.ends_with("-F") {
+        return Some(MON_FEMALE);
+    }
+    national_dex(species).and_then(|dex| GENDER_RATIOS.get(dex as usize).copied())
+}
+
 /// Look up ability name given species name and ability slot (0=primary, 1=secondary, 2=hidden).
+/// Forms without their own row ("Unown-B", "Cubone-Alola") use the base species' abilities.
 pub fn ability_name(species: &str, slot: u8) -> String {
-    match ABILITIES.get(&species.to_lowercase()) {
+    let mut name = species;
+    let abilities = loop {
+        if let Some(abilities) = ABILITIES.get(&ability_key(name)) {
+            break Some(abilities);
+        }
+        match name.rfind('-') {
+            Some(i) => name = &name[..i],
+            None => break None,
+        }
+    };
+    match abilities {
         Some((primary, secondary, hidden)) => match slot {
             2 => hidden.clone(),
             1 => secondary.clone(),
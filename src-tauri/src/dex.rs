@@ -0,0 +1,88 @@
+//! Pokedex completion: which species have been seen and caught.
+
+use serde::Serialize;
+
+use crate::data;
+use crate::error::ParseError;
+use crate::parser::{self, Section};
+use crate::profile::GameProfile;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct DexEntry {
+    pub dex: u16,
+    pub species: &'static str,
+    pub seen: bool,
+    pub caught: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DexProgress {
+    pub seen_count: usize,
+    pub caught_count: usize,
+    /// Every species the game's dex covers, in national dex order.
+    pub entries: Vec<DexEntry>,
+}
+
+fn flag_set(flags: &[u8], index: usize) -> bool {
+    flags[index / 8] & (1 << (index % 8)) != 0
+}
+
+pub fn parse_dex_progress(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<DexProgress, ParseError> {
+    let section = parser::find_section(sections, profile.dex_section)?;
+    let flag_bytes = profile.dex_species_count.div_ceil(8);
+    let caught = parser::bytes_at(section, profile.dex_caught_offset, flag_bytes)?;
+    let seen = parser::bytes_at(section, profile.dex_seen_offset, flag_bytes)?;
+
+    let entries: Vec<DexEntry> = (0..profile.dex_species_count)
+        .map(|i| {
+            let dex = i as u16 + 1;
+            DexEntry {
+                dex,
+                species: data::national_species(dex).unwrap_or("???"),
+                // Catching a species also marks it seen
+                seen: flag_set(seen, i) || flag_set(caught, i),
+                caught: flag_set(caught, i),
+            }
+        })
+        .collect();
+
+    Ok(DexProgress {
+        seen_count: entries.iter().filter(|e| e.seen).count(),
+        caught_count: entries.iter().filter(|e| e.caught).count(),
+        entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_parse_dex_progress() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let progress = parse_dex_progress(&sections, &RADICAL_RED).expect("Failed to parse dex");
+        assert_eq!((progress.seen_count, progress.caught_count), (102, 27));
+        assert_eq!(progress.entries.len(), 1025);
+        // Everything in the party has been caught
+        for dex in [24, 73, 404, 435, 921, 974] {
+            let entry = &progress.entries[dex - 1];
+            assert!(entry.caught, "{} not caught", entry.species);
+        }
+        assert_eq!(
+            progress.entries[0],
+            DexEntry {
+                dex: 1,
+                species: "Bulbasaur",
+                seen: true,
+                caught: false
+            }
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Error types. Commands return `AppError`, which reaches the frontend as a
+//! `code` it can branch on to offer a fix, plus a `message` to show.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use thiserror::Error;
+
+use crate::parser::Location;
+
+/// Why a save couldn't be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Failed to read file: {0}")]
+    Io(String),
+    #[error("Save file kept changing while being read; is it still syncing?")]
+    Unstable,
+    #[error("File too small to be a valid .sav ({len} bytes)")]
+    TooSmall { len: usize },
+    #[error("No completed save found; save in-game at least once first")]
+    NoCompletedSave,
+    #[error("Section {id} not found")]
+    MissingSection { id: u16 },
+    #[error("Save data ended early: needed {len} bytes at {offset:#x}, only {size} available")]
+    OutOfBounds {
+        offset: usize,
+        len: usize,
+        size: usize,
+    },
+    #[error("The {feature} isn't supported for this game yet")]
+    Unsupported { feature: &'static str },
+    #[error("No party found in this save; has the game been started?")]
+    EmptyParty,
+    #[error("{0} saves aren't supported yet")]
+    UnsupportedGame(&'static str),
+    #[error("Checksum mismatch: expected {expected:#06x}, got {actual:#06x}")]
+    ChecksumMismatch { expected: u16, actual: u16 },
+    #[error("Expected {expected} bytes, got {actual}")]
+    BadLength { expected: usize, actual: usize },
+    #[error("{kind} {id} does not exist in vanilla")]
+    NotInVanilla { kind: &'static str, id: u16 },
+}
+
+impl ParseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::Io(_) => "io",
+            ParseError::Unstable => "unstable",
+            ParseError::TooSmall { .. } => "too_small",
+            ParseError::NoCompletedSave => "no_completed_save",
+            ParseError::MissingSection { .. } => "missing_section",
+            ParseError::OutOfBounds { .. } => "out_of_bounds",
+            ParseError::Unsupported { .. } => "unsupported",
+            ParseError::EmptyParty => "empty_party",
+            ParseError::UnsupportedGame(_) => "unsupported_game",
+            ParseError::ChecksumMismatch { .. } => "checksum_mismatch",
+            ParseError::BadLength { .. } => "bad_length",
+            ParseError::NotInVanilla { .. } => "not_in_vanilla",
+        }
+    }
+}
+
+/// Why Pokemon couldn't be picked out of, or projected from, a parsed save.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QueryError {
+    #[error("No Pokemon in party slot {slot}")]
+    EmptySlot { slot: usize },
+    #[error("No Pokemon at {location:?}")]
+    NoPokemonAt { location: Location },
+    #[error("There is no box {box_number}")]
+    NoSuchBox { box_number: usize },
+    #[error("Unknown Pokemon field: {0}")]
+    UnknownField(String),
+    #[error("Failed to serialize Pokemon: {0}")]
+    Serialize(String),
+}
+
+impl QueryError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            QueryError::EmptySlot { .. } => "empty_slot",
+            QueryError::NoPokemonAt { .. } => "no_pokemon_at",
+            QueryError::NoSuchBox { .. } => "no_such_box",
+            QueryError::UnknownField(_) => "unknown_field",
+            QueryError::Serialize(_) => "serialize",
+        }
+    }
+}
+
+/// Why an export couldn't be written to disk.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExportError {
+    #[error("Failed to create folder: {0}")]
+    CreateDir(String),
+    #[error("Failed to serialize party: {0}")]
+    Serialize(String),
+    #[error("Failed to write file: {0}")]
+    Write(String),
+}
+
+/// Why the settings file couldn't be written.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SettingsError {
+    #[error("Failed to get app data dir: {0}")]
+    AppDataDir(String),
+    #[error("Failed to serialize settings: {0}")]
+    Serialize(String),
+    #[error("Failed to write settings: {0}")]
+    Write(String),
+}
+
+/// Why text couldn't be written as a Gen 3 string.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EncodeError {
+    #[error("'{0}' can't be written in a Gen 3 string")]
+    Unencodable(char),
+    #[error("\"{text}\" is longer than {len} characters")]
+    TooLong { text: String, len: usize },
+}
+
+/// Why a PKHeX box report couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReportError {
+    #[error("Box report has no {0} column")]
+    MissingColumn(&'static str),
+    #[error("Box report row {row}: {reason}")]
+    BadRow { row: usize, reason: String },
+    #[error("Box report isn't valid JSON: {0}")]
+    Json(String),
+}
+
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Settings(#[from] SettingsError),
+    #[error(transparent)]
+    Query(#[from] QueryError),
+    #[error(transparent)]
+    Export(#[from] ExportError),
+    #[error(transparent)]
+    Encode(#[from] EncodeError),
+    #[error(transparent)]
+    Report(#[from] ReportError),
+    #[error("No save loaded")]
+    NoSaveLoaded,
+    /// Failed checks of the self-test, which only reports them as text.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Parse(e) => e.code(),
+            AppError::Settings(_) => "settings",
+            AppError::Query(e) => e.code(),
+            AppError::Export(_) => "export",
+            AppError::Encode(_) => "encode",
+            AppError::Report(_) => "report",
+            AppError::NoSaveLoaded => "no_save_loaded",
+            AppError::Other(_) => "other",
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_error_serializes_code_and_message() {
+        let error = AppError::from(ParseError::TooSmall { len: 16 });
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "too_small");
+        assert_eq!(
+            json["message"],
+            "File too small to be a valid .sav (16 bytes)"
+        );
+
+        let encode = AppError::from(EncodeError::Unencodable('日'));
+        assert_eq!(serde_json::to_value(&encode).unwrap()["code"], "encode");
+
+        let other = serde_json::to_value(AppError::Other("boom".to_string())).unwrap();
+        assert_eq!(other["code"], "other");
+    }
+}
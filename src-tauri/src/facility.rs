@@ -0,0 +1,167 @@
+//! Battle facility export: three or four party members checked against the
+//! facility rules and previewed at the level they'd be scaled to.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::error::QueryError;
+use crate::parser::{self, Pokemon};
+
+/// Battle Tower style facilities scale everyone to this level.
+pub const FACILITY_LEVEL: u8 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacilityFormat {
+    Singles,
+    Doubles,
+}
+
+impl FacilityFormat {
+    pub fn team_size(self) -> usize {
+        match self {
+            FacilityFormat::Singles => 3,
+            FacilityFormat::Doubles => 4,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FacilityIssue {
+    TeamSize {
+        expected: usize,
+        found: usize,
+    },
+    /// Species clause: no two Pokemon of the same species.
+    DuplicateSpecies {
+        species: String,
+    },
+    /// Item clause: no two Pokemon holding the same item.
+    DuplicateItem {
+        item: String,
+    },
+    Egg {
+        slot: usize,
+    },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FacilityTeam {
+    /// The chosen Pokemon with level, stats and display text scaled to
+    /// `FACILITY_LEVEL`.
+    pub pokemon: Vec<Pokemon>,
+    pub issues: Vec<FacilityIssue>,
+    /// Showdown text for the whole team.
+    pub text: String,
+}
+
+fn check_rules(chosen: &[(usize, &Pokemon)], format: FacilityFormat) -> Vec<FacilityIssue> {
+    let mut issues = Vec::new();
+    if chosen.len() != format.team_size() {
+        issues.push(FacilityIssue::TeamSize {
+            expected: format.team_size(),
+            found: chosen.len(),
+        });
+    }
+    let mut species = HashSet::new();
+    let mut items = HashSet::new();
+    for &(slot, mon) in chosen {
+        if mon.is_egg {
+            issues.push(FacilityIssue::Egg { slot });
+            continue;
+        }
+        if !species.insert(&mon.species) {
+            issues.push(FacilityIssue::DuplicateSpecies {
+                species: mon.species.clone(),
+            });
+        }
+        if let Some(item) = mon.item.as_ref().filter(|item| !items.insert(*item)) {
+            issues.push(FacilityIssue::DuplicateItem { item: item.clone() });
+        }
+    }
+    issues
+}
+
+/// Build a facility team from the party members in `slots`. Rule violations
+/// are reported rather than refused, so the team can be fixed up in the UI.
+pub fn facility_team(
+    party: &[Pokemon],
+    slots: &[usize],
+    format: FacilityFormat,
+    clean_paste: bool,
+) -> Result<FacilityTeam, QueryError> {
+    let chosen = slots
+        .iter()
+        .map(|&slot| {
+            party
+                .get(slot)
+                .map(|mon| (slot, mon))
+                .ok_or(QueryError::EmptySlot { slot })
+        })
+        .collect::<Result<Vec<_>, QueryError>>()?;
+    let issues = check_rules(&chosen, format);
+
+    let pokemon: Vec<Pokemon> = chosen
+        .iter()
+        .map(|&(_, mon)| {
+            let mut scaled = mon.clone();
+            scaled.level = FACILITY_LEVEL;
+            scaled.stats = parser::stats_at_level(mon, FACILITY_LEVEL);
+            scaled.display_text = parser::build_display_text(&scaled, clean_paste);
+            scaled
+        })
+        .collect();
+    let text = pokemon
+        .iter()
+        .map(|mon| mon.display_text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Ok(FacilityTeam {
+        pokemon,
+        issues,
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::rr_party;
+
+    #[test]
+    fn test_facility_team() {
+        let mut party = rr_party();
+
+        let team = facility_team(&party, &[2, 4, 5], FacilityFormat::Singles, false)
+            .expect("Slots should exist");
+        assert!(team.issues.is_empty());
+        assert!(team.pokemon.iter().all(|m| m.level == FACILITY_LEVEL));
+        assert!(team.pokemon[0].display_text.contains("Level: 50\n"));
+        assert!(team
+            .pokemon
+            .iter()
+            .zip([&party[2], &party[4], &party[5]])
+            .all(|(scaled, mon)| scaled.stats.unwrap()[0] > mon.stats.unwrap()[0]));
+        assert_eq!(team.text.matches("Level: 50").count(), 3);
+
+        party[0].item = Some("Oran Berry".to_string());
+        let team = facility_team(&party, &[0, 3], FacilityFormat::Doubles, false)
+            .expect("Slots should exist");
+        assert_eq!(
+            team.issues,
+            [
+                FacilityIssue::TeamSize {
+                    expected: 4,
+                    found: 2
+                },
+                FacilityIssue::DuplicateItem {
+                    item: "Oran Berry".to_string()
+                },
+            ]
+        );
+
+        assert!(facility_team(&party, &[0, 6, 1], FacilityFormat::Singles, false).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Field projection and paging for large results: lightweight views ask for
+//! just the Pokemon fields they show, a page at a time, so only those are
+//! sent to the frontend.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::QueryError;
+use crate::parser::{PcBox, Pokemon};
+
+/// Always kept so a projected Pokemon can still be referred back to.
+const ALWAYS_KEPT: &str = "location";
+
+/// A Pokemon as JSON with only `fields` (plus its location) kept. Repeated
+/// fields are kept once. Fails on a field name Pokemon doesn't have.
+///
+/// The whole Pokemon is still serialized before the other fields are
+/// dropped, so this trims what crosses to the frontend, not the work of
+/// serializing it.
+pub fn project_pokemon(mon: &Pokemon, fields: &[String]) -> Result<Value, QueryError> {
+    let Value::Object(mut full) =
+        serde_json::to_value(mon).map_err(|e| QueryError::Serialize(e.to_string()))?
+    else {
+        return Err(QueryError::Serialize("not a JSON object".to_string()));
+    };
+    let mut projected = Map::new();
+    for field in fields.iter().map(String::as_str).chain([ALWAYS_KEPT]) {
+        if projected.contains_key(field) {
+            continue;
+        }
+        let value = full
+            .remove(field)
+            .ok_or_else(|| QueryError::UnknownField(field.to_string()))?;
+        projected.insert(field.to_string(), value);
+    }
+    Ok(Value::Object(projected))
+}
+
+/// Boxes with each Pokemon projected down to `fields`.
+pub fn project_boxes(boxes: &[PcBox], fields: &[String]) -> Result<Value, QueryError> {
+    boxes
+        .iter()
+        .map(|pc_box| {
+            let pokemon = pc_box
+                .pokemon
+                .iter()
+                .map(|mon| project_pokemon(mon, fields))
+                .collect::<Result<Vec<_>, QueryError>>()?;
+            Ok(serde_json::json!({ "number": pc_box.number, "pokemon": pokemon }))
+        })
+        .collect::<Result<Vec<_>, QueryError>>()
+        .map(Value::Array)
+}
+
+/// One page of a long list, with the full length so a virtualized list can
+/// be sized before every page is fetched.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Page<T> {
+    pub total: usize,
+    pub offset: usize,
+    pub items: Vec<T>,
+}
+
+/// At most `limit` of `items`, starting at `offset`. An offset past the end
+/// gives an empty page.
+pub fn paginate<T>(items: Vec<T>, offset: usize, limit: usize) -> Page<T> {
+    let total = items.len();
+    Page {
+        total,
+        offset,
+        items: items.into_iter().skip(offset).take(limit).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_project_boxes() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+
+        let fields = ["species".to_string(), "level".to_string()];
+        let projected = project_boxes(&boxes, &fields).expect("Fields exist");
+        let cubchoo = &projected[0]["pokemon"][0];
+        assert_eq!(projected[0]["number"], 1);
+        assert_eq!(cubchoo["species"], "Cubchoo");
+        assert_eq!(cubchoo["level"], 28);
+        assert_eq!(cubchoo["location"]["kind"], "box");
+        assert_eq!(cubchoo.as_object().unwrap().len(), 3);
+
+        assert!(project_boxes(&boxes, &["shoe_size".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_project_repeated_fields() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+        let cubchoo = &boxes[0].pokemon[0];
+
+        let location = project_pokemon(cubchoo, &["location".to_string()]).expect("Field exists");
+        assert_eq!(location.as_object().unwrap().len(), 1);
+        assert_eq!(location["location"]["kind"], "box");
+
+        let fields = ["level".to_string(), "level".to_string()];
+        let level = project_pokemon(cubchoo, &fields).expect("Field exists");
+        assert_eq!(level.as_object().unwrap().len(), 2);
+        assert_eq!(level["level"], 28);
+    }
+
+    #[test]
+    fn test_paginate() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+        let boxed: Vec<_> = boxes
+            .iter()
+            .flat_map(|b| &b.pokemon)
+            .map(|m| m.location)
+            .collect();
+        assert!(boxed.len() > 3);
+
+        let page = paginate(boxed.clone(), 1, 2);
+        assert_eq!((page.total, page.offset), (boxed.len(), 1));
+        assert_eq!(page.items, boxed[1..3]);
+
+        let last = paginate(boxed.clone(), boxed.len() - 1, 10);
+        assert_eq!(last.items, boxed[boxed.len() - 1..]);
+        let past_end = paginate(boxed.clone(), boxed.len() + 5, 10);
+        assert!(past_end.items.is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+//! Hall of Fame records, kept in two flash sectors after the save slots.
+
+use serde::Serialize;
+
+use crate::charmap::{decode_gen3_string_in, Charset};
+use crate::data;
+use crate::error::ParseError;
+use crate::parser::{u16_le, u32_le, SECTION_SIZE, SLOT_SIZE};
+use crate::pk3::Format;
+use crate::profile::GameProfile;
+
+/// The Hall of Fame takes the two sectors right after both save slots, using
+/// the same data size per sector as SaveBlock1.
+const HOF_OFFSET: usize = SLOT_SIZE * 2;
+const HOF_SECTORS: usize = 2;
+const HOF_SECTOR_DATA: usize = 0xF80;
+const HOF_TEAM_COUNT: usize = 50;
+const HOF_MON_SIZE: usize = 20;
+const HOF_TEAM_SIZE: usize = HOF_MON_SIZE * 6;
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct HallOfFameMon {
+    pub species: String,
+    pub level: u8,
+    pub nickname: String,
+    pub trainer_id: u16,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HallOfFameTeam {
+    /// 1 for the oldest recorded team. Once all 50 slots are full the game
+    /// drops the oldest, so this counts from the oldest still kept.
+    pub number: usize,
+    pub pokemon: Vec<HallOfFameMon>,
+}
+
+/// Layout: trainer ID(u32), personality(u32), species(9 bits) and
+/// level(7 bits) packed into a u16, nickname(10).
+fn parse_hof_mon(raw: &[u8], charset: Charset) -> Option<HallOfFameMon> {
+    let packed = u16_le(raw, 8);
+    let species = packed & 0x1FF;
+    if species == 0 || u32_le(raw, 0) == u32::MAX {
+        return None;
+    }
+    Some(HallOfFameMon {
+        species: data::species_name(species).to_string(),
+        level: (packed >> 9) as u8,
+        nickname: decode_gen3_string_in(&raw[10..20], charset),
+        trainer_id: u16_le(raw, 0),
+    })
+}
+
+/// Every team in the Hall of Fame, oldest first. Saves without the Hall of
+/// Fame sectors (64KiB saves, or a game never beaten on this cartridge) have none.
+/// Nicknames are decoded with the save's `charset`.
+///
+/// CFRU species IDs don't fit the vanilla 9-bit field, and how it records
+/// them hasn't been checked against a save with a Hall of Fame, so its
+/// records are refused rather than read as the wrong species.
+pub fn parse_hall_of_fame(
+    raw: &[u8],
+    profile: &GameProfile,
+    charset: Charset,
+) -> Result<Vec<HallOfFameTeam>, ParseError> {
+    if profile.pokemon_format == Format::Cfru {
+        return Err(ParseError::Unsupported {
+            feature: "Hall of Fame",
+        });
+    }
+    if raw.len() < HOF_OFFSET + SECTION_SIZE * HOF_SECTORS {
+        return Ok(Vec::new());
+    }
+    let data: Vec<u8> = (0..HOF_SECTORS)
+        .flat_map(|i| {
+            let start = HOF_OFFSET + i * SECTION_SIZE;
+            raw[start..start + HOF_SECTOR_DATA].iter().copied()
+        })
+        .collect();
+
+    // Teams are recorded in order, so the first empty one ends the list
+    Ok((0..HOF_TEAM_COUNT)
+        .map(|i| &data[i * HOF_TEAM_SIZE..(i + 1) * HOF_TEAM_SIZE])
+        .map(|team| -> Vec<HallOfFameMon> {
+            team.chunks(HOF_MON_SIZE)
+                .map_while(|mon| parse_hof_mon(mon, charset))
+                .collect()
+        })
+        .take_while(|pokemon| !pokemon.is_empty())
+        .enumerate()
+        .map(|(i, pokemon)| HallOfFameTeam {
+            number: i + 1,
+            pokemon,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{self, test_support::TEST_SAV};
+    use crate::profile::{FIRE_RED_LEAF_GREEN, RADICAL_RED};
+    use crate::savegen;
+
+    /// Record party Pokemon `slot` of the sample save as Hall of Fame entry
+    /// `entry`, the way the game copies it in.
+    fn record_party_mon(raw: &mut [u8], entry: usize, slot: usize) {
+        let sections = parser::active_slot(raw).expect("Failed to load save");
+        let party = parser::find_section(&sections, RADICAL_RED.party_section).unwrap();
+        let size = RADICAL_RED.pokemon.size;
+        let pkmn = &party[RADICAL_RED.party_offset + slot * size..][..size];
+        let layout = &RADICAL_RED.pokemon;
+        let packed = u16_le(pkmn, layout.growth) | (pkmn[layout.level] as u16) << 9;
+
+        let mut record = [0u8; HOF_MON_SIZE];
+        record[0..4].copy_from_slice(&pkmn[4..8]);
+        record[4..8].copy_from_slice(&pkmn[0..4]);
+        record[8..10].copy_from_slice(&packed.to_le_bytes());
+        record[10..20].copy_from_slice(&pkmn[8..18]);
+        let off = HOF_OFFSET + entry * HOF_MON_SIZE;
+        raw[off..off + HOF_MON_SIZE].copy_from_slice(&record);
+    }
+
+    #[test]
+    fn test_parse_hall_of_fame() {
+        let raw = savegen::demo_save().unwrap();
+        let teams = parse_hall_of_fame(&raw, &FIRE_RED_LEAF_GREEN, Charset::International);
+        assert!(teams.unwrap().is_empty());
+
+        // Tentacruel and Arbok from the sample save, whose species fit 9 bits
+        let mut raw = std::fs::read(TEST_SAV).unwrap();
+        record_party_mon(&mut raw, 0, 0);
+        record_party_mon(&mut raw, 1, 3);
+        record_party_mon(&mut raw, 6, 0);
+        let teams = parse_hall_of_fame(&raw, &FIRE_RED_LEAF_GREEN, Charset::International)
+            .expect("Failed to parse Hall of Fame");
+
+        assert_eq!(teams.len(), 2);
+        assert_eq!(teams[0].pokemon.len(), 2);
+        assert_eq!(teams[1].number, 2);
+        assert_eq!(
+            teams[0].pokemon[1],
+            HallOfFameMon {
+                species: "Arbok".to_string(),
+                level: 28,
+                nickname: "Kaeman".to_string(),
+                trainer_id: 19242,
+            }
+        );
+        assert_eq!(teams[1].pokemon[0].nickname, "2Kewl");
+    }
+
+    #[test]
+    fn test_erased_hall_of_fame() {
+        let raw = std::fs::read(TEST_SAV).unwrap();
+        let teams = parse_hall_of_fame(&raw, &FIRE_RED_LEAF_GREEN, Charset::International);
+        assert!(teams.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cfru_hall_of_fame_unsupported() {
+        let raw = std::fs::read(TEST_SAV).unwrap();
+        assert!(matches!(
+            parse_hall_of_fame(&raw, &RADICAL_RED, Charset::International),
+            Err(ParseError::Unsupported { .. })
+        ));
+    }
+}
@@ -1,14 +1,572 @@
+mod archive;
+mod bag;
+mod bulk;
 mod charmap;
+mod corpus;
 mod data;
+mod dex;
+mod error;
+mod facility;
+mod fields;
+mod halloffame;
+mod mail;
+mod minigames;
 mod parser;
+mod perf;
+pub mod pk3;
+mod pkhex;
+mod profile;
+mod progression;
+mod projection;
+mod reader;
+mod readiness;
+mod rematch;
+mod rules;
+mod savegen;
+mod selftest;
+mod session;
 mod settings;
+mod setup;
+mod trainer;
 
-use tauri::AppHandle;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
 
-#[tauri::command]
-fn parse_sav_file(app: AppHandle, path: String) -> Result<Vec<parser::Pokemon>, String> {
+use error::{AppError, ExportError, ParseError, QueryError};
+
+/// Read the save at `path` using the configured IO mode and pick its active slot.
+/// The sync-safe mode sleeps between retries, so commands that read a save are
+/// `async` to keep that off the main thread.
+fn load_sections(app: &AppHandle, path: &str) -> Result<Vec<parser::Section>, ParseError> {
+    parser::load_active_slot(path, settings::get_io_mode(app))
+}
+
+/// The configured game, or the one detected from the save when none is set.
+fn resolve_game(
+    app: &AppHandle,
+    sections: &[parser::Section],
+) -> Result<profile::Game, ParseError> {
+    match settings::get_game(app) {
+        Some(game) => Ok(game),
+        None => parser::detect_game(sections),
+    }
+}
+
+/// Parse the save at `path` and make it the current save. `options` picks
+/// what to decode beyond the party; by default only the party is. The file
+/// is always read again, but an unchanged save isn't parsed twice.
+#[tauri::command(async)]
+fn parse_sav_file(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    save: State<session::SaveState>,
+    cache: State<session::ParseCache>,
+    path: String,
+    options: Option<parser::ParseOptions>,
+) -> Result<perf::WithMeta<parser::ParseResult>, AppError> {
     settings::set_saved_path(&app, &path)?;
-    parser::parse_sav(&path)
+    let parsed = perf.time_with_meta("parse_sav_file", || {
+        cache.parse(
+            reader::read_save(&path, settings::get_io_mode(&app))?,
+            settings::get_game(&app),
+            options.unwrap_or_default(),
+            settings::get_clean_paste(&app),
+        )
+    })?;
+    publish_party(&app, &save, Some(path), parsed)
+}
+
+/// Parse a save handed over as bytes, for platforms where the file picker
+/// gives a content URI rather than a path (Android's storage access framework).
+/// Nothing is remembered for next launch.
+#[tauri::command]
+fn parse_sav_bytes(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    save: State<session::SaveState>,
+    cache: State<session::ParseCache>,
+    bytes: Vec<u8>,
+    options: Option<parser::ParseOptions>,
+) -> Result<perf::WithMeta<parser::ParseResult>, AppError> {
+    let parsed = perf.time_with_meta("parse_sav_bytes", || {
+        reader::count_bytes_read(bytes.len());
+        cache.parse(
+            bytes,
+            settings::get_game(&app),
+            options.unwrap_or_default(),
+            settings::get_clean_paste(&app),
+        )
+    })?;
+    publish_party(&app, &save, None, parsed)
+}
+
+/// Apply the party filters, archive the party and make it the current save.
+fn publish_party(
+    app: &AppHandle,
+    save: &session::SaveState,
+    path: Option<String>,
+    parsed: perf::WithMeta<parser::ParseResult>,
+) -> Result<perf::WithMeta<parser::ParseResult>, AppError> {
+    let perf::WithMeta { mut result, meta } = parsed;
+    if settings::get_own_catches_only(app) {
+        result.party.retain(|mon| !mon.is_traded);
+        for pc_box in result.boxes.iter_mut().flatten() {
+            pc_box.pokemon.retain(|mon| !mon.is_traded);
+        }
+        if let Some(boxes) = &mut result.boxes {
+            boxes.retain(|pc_box| !pc_box.pokemon.is_empty());
+        }
+    }
+    if let Some(dir) = settings::get_archive_dir(app) {
+        archive::archive_party(
+            Path::new(&dir),
+            chrono::Local::now(),
+            "party",
+            &result.party,
+        )?;
+    }
+    save.publish(
+        app,
+        session::LoadedSave {
+            path,
+            game: result.game,
+            corrupt_sections: result.corrupt_sections.clone(),
+            rule_warnings: rules::evaluate(&settings::get_rules(app), &result.party),
+            party: result.party.clone(),
+        },
+    );
+    Ok(perf::WithMeta { result, meta })
+}
+
+#[tauri::command]
+fn load_demo(
+    app: AppHandle,
+    save: State<session::SaveState>,
+) -> Result<Vec<parser::Pokemon>, AppError> {
+    let game = profile::Game::RadicalRed;
+    let sections = parser::active_slot(&savegen::demo_save()?)?;
+    let party = parser::parse_party(&sections, game.profile(), settings::get_clean_paste(&app))?;
+    save.publish(
+        &app,
+        session::LoadedSave {
+            path: None,
+            game,
+            corrupt_sections: Vec::new(),
+            rule_warnings: rules::evaluate(&settings::get_rules(&app), &party),
+            party: party.clone(),
+        },
+    );
+    Ok(party)
+}
+
+/// The save last parsed by any window, for windows opened after it was loaded.
+#[tauri::command]
+fn get_current_save(save: State<session::SaveState>) -> Option<session::LoadedSave> {
+    save.current()
+}
+
+/// Pre-battle check of the current save's party. `level_cap` is the cap for
+/// the next fight, if the player is following one.
+#[tauri::command]
+fn team_readiness(
+    save: State<session::SaveState>,
+    level_cap: Option<u8>,
+) -> Result<Vec<readiness::ReadinessWarning>, AppError> {
+    let current = save.current().ok_or(AppError::NoSaveLoaded)?;
+    Ok(readiness::team_readiness(&current.party, level_cap))
+}
+
+/// Facility export of the party members in `slots` from the current save.
+#[tauri::command]
+fn export_facility_team(
+    app: AppHandle,
+    save: State<session::SaveState>,
+    slots: Vec<usize>,
+    format: facility::FacilityFormat,
+) -> Result<facility::FacilityTeam, AppError> {
+    let current = save.current().ok_or(AppError::NoSaveLoaded)?;
+    Ok(facility::facility_team(
+        &current.party,
+        &slots,
+        format,
+        settings::get_clean_paste(&app),
+    )?)
+}
+
+/// Level 50 and 100 stat projections for the current save's party.
+#[tauri::command]
+fn project_stats(
+    save: State<session::SaveState>,
+) -> Result<Vec<projection::StatProjection>, AppError> {
+    let current = save.current().ok_or(AppError::NoSaveLoaded)?;
+    Ok(projection::project_stats(&current.party))
+}
+
+/// The non-empty PC boxes of the save at `path`, without traded Pokemon when
+/// only the player's own catches are shown.
+fn load_boxes(
+    app: &AppHandle,
+    perf: &perf::PerfStats,
+    command: &str,
+    path: &str,
+) -> Result<Vec<parser::PcBox>, ParseError> {
+    let clean_paste = settings::get_clean_paste(app);
+    let mut boxes = perf.time(command, || {
+        let sections = load_sections(app, path)?;
+        let game = resolve_game(app, &sections)?;
+        parser::parse_pc_boxes(&sections, game.profile(), clean_paste)
+    })?;
+    if settings::get_own_catches_only(app) {
+        for pc_box in &mut boxes {
+            pc_box.pokemon.retain(|mon| !mon.is_traded);
+        }
+        boxes.retain(|pc_box| !pc_box.pokemon.is_empty());
+    }
+    Ok(boxes)
+}
+
+/// Every non-empty PC box. With `fields`, each Pokemon only carries those
+/// fields and its location, for views that don't need full sets.
+#[tauri::command(async)]
+fn parse_boxes(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+    fields: Option<Vec<String>>,
+) -> Result<serde_json::Value, AppError> {
+    let boxes = load_boxes(&app, &perf, "parse_boxes", &path)?;
+    Ok(match fields {
+        Some(fields) => fields::project_boxes(&boxes, &fields)?,
+        None => serde_json::to_value(&boxes).map_err(|e| QueryError::Serialize(e.to_string()))?,
+    })
+}
+
+/// Boxed Pokemon in box and slot order, `limit` at a time from `offset`,
+/// with the total for sizing a virtualized list. `fields` works as in
+/// `parse_boxes`.
+#[tauri::command(async)]
+fn list_boxed_pokemon(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+    offset: usize,
+    limit: usize,
+    fields: Option<Vec<String>>,
+) -> Result<fields::Page<serde_json::Value>, AppError> {
+    let boxes = load_boxes(&app, &perf, "list_boxed_pokemon", &path)?;
+    let boxed: Vec<&parser::Pokemon> = boxes.iter().flat_map(|b| &b.pokemon).collect();
+    let page = fields::paginate(boxed, offset, limit);
+    let items = page
+        .items
+        .into_iter()
+        .map(|mon| match &fields {
+            Some(fields) => fields::project_pokemon(mon, fields),
+            None => serde_json::to_value(mon).map_err(|e| QueryError::Serialize(e.to_string())),
+        })
+        .collect::<Result<Vec<_>, QueryError>>()?;
+    Ok(fields::Page {
+        total: page.total,
+        offset: page.offset,
+        items,
+    })
+}
+
+type PartyAndBoxes = (
+    &'static profile::GameProfile,
+    Vec<parser::Pokemon>,
+    Vec<parser::PcBox>,
+);
+
+/// The party and boxes of the save at `path`, for the bulk commands.
+fn load_party_and_boxes(app: &AppHandle, path: &str) -> Result<PartyAndBoxes, AppError> {
+    let clean_paste = settings::get_clean_paste(app);
+    let sections = load_sections(app, path)?;
+    let profile = resolve_game(app, &sections)?.profile();
+    let party = parser::parse_party(&sections, profile, clean_paste)?;
+    let boxes = parser::parse_pc_boxes(&sections, profile, clean_paste)?;
+    Ok((profile, party, boxes))
+}
+
+/// Showdown text for the Pokemon at `locations` in the save at `path`.
+#[tauri::command(async)]
+fn export_selected(
+    app: AppHandle,
+    path: String,
+    locations: Vec<parser::Location>,
+) -> Result<String, AppError> {
+    let (_, party, boxes) = load_party_and_boxes(&app, &path)?;
+    Ok(bulk::export_selected(&party, &boxes, &locations)?)
+}
+
+/// Box `box_number` of the save at `path`, or every box without it, as a
+/// Showdown teambuilder import filed under `folder`.
+#[tauri::command(async)]
+fn export_box(
+    app: AppHandle,
+    path: String,
+    box_number: Option<usize>,
+    folder: String,
+) -> Result<String, AppError> {
+    let (profile, _, boxes) = load_party_and_boxes(&app, &path)?;
+    Ok(bulk::export_boxes(&boxes, box_number, &folder, profile)?)
+}
+
+/// Differences between the boxes of the save at `path` and the PKHeX box
+/// report at `report_path`, to check edits made in PKHeX.
+#[tauri::command(async)]
+fn compare_box_report(
+    app: AppHandle,
+    path: String,
+    report_path: String,
+) -> Result<pkhex::ReportDiff, AppError> {
+    let text = std::fs::read_to_string(&report_path).map_err(|e| ParseError::Io(e.to_string()))?;
+    let report = pkhex::parse_report(&text)?;
+    let (_, _, boxes) = load_party_and_boxes(&app, &path)?;
+    Ok(pkhex::diff_report(&report, &boxes))
+}
+
+/// What releasing the Pokemon at `locations` would lose. Nothing is released.
+#[tauri::command(async)]
+fn preview_release(
+    app: AppHandle,
+    path: String,
+    locations: Vec<parser::Location>,
+) -> Result<bulk::ReleasePreview, AppError> {
+    let (_, party, boxes) = load_party_and_boxes(&app, &path)?;
+    Ok(bulk::preview_release(&party, &boxes, &locations)?)
+}
+
+/// Where the Pokemon at `locations` would go if moved to box `box_number`.
+/// Nothing is moved.
+#[tauri::command(async)]
+fn preview_move(
+    app: AppHandle,
+    path: String,
+    locations: Vec<parser::Location>,
+    box_number: usize,
+) -> Result<bulk::MovePreview, AppError> {
+    let (profile, party, boxes) = load_party_and_boxes(&app, &path)?;
+    Ok(bulk::preview_move(
+        &party, &boxes, &locations, box_number, profile,
+    )?)
+}
+
+#[tauri::command(async)]
+fn parse_daycare(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<Vec<parser::DaycareMon>, AppError> {
+    let clean_paste = settings::get_clean_paste(&app);
+    perf.time("parse_daycare", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(parser::parse_daycare(
+            &sections,
+            game.profile(),
+            clean_paste,
+        )?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_trainer_info(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<trainer::TrainerInfo, AppError> {
+    perf.time("parse_trainer_info", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(trainer::parse_trainer_info(&sections, game.profile())?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_bag(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<bag::Inventory, AppError> {
+    perf.time("parse_bag", || {
+        let raw = reader::read_save(&path, settings::get_io_mode(&app))?;
+        let sections = parser::active_slot(&raw)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(bag::parse_bag(&raw, &sections, game.profile())?)
+    })
+}
+
+/// Badges, story key items and the current level cap from the configured caps.
+#[tauri::command(async)]
+fn story_progress(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<progression::StoryProgress, AppError> {
+    perf.time("story_progress", || {
+        let raw = reader::read_save(&path, settings::get_io_mode(&app))?;
+        let sections = parser::active_slot(&raw)?;
+        let game = resolve_game(&app, &sections)?;
+        let level_caps = settings::get_level_caps(&app);
+        Ok(progression::story_progress(
+            &raw,
+            &sections,
+            game.profile(),
+            &level_caps,
+        )?)
+    })
+}
+
+#[tauri::command(async)]
+fn get_dex_progress(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<dex::DexProgress, AppError> {
+    perf.time("get_dex_progress", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(dex::parse_dex_progress(&sections, game.profile())?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_hall_of_fame(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<Vec<halloffame::HallOfFameTeam>, AppError> {
+    perf.time("parse_hall_of_fame", || {
+        let raw = reader::read_save(&path, settings::get_io_mode(&app))?;
+        let sections = parser::active_slot(&raw)?;
+        let game = resolve_game(&app, &sections)?;
+        let charset = parser::save_charset(&sections, game.profile())?;
+        Ok(halloffame::parse_hall_of_fame(
+            &raw,
+            game.profile(),
+            charset,
+        )?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_mail(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<Vec<mail::Mail>, AppError> {
+    perf.time("parse_mail", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(mail::parse_mail(&sections, game.profile())?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_rematch_state(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<rematch::RematchState, AppError> {
+    perf.time("parse_rematch_state", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(rematch::parse_rematch_state(&sections, game.profile())?)
+    })
+}
+
+#[tauri::command(async)]
+fn parse_minigame_records(
+    app: AppHandle,
+    perf: State<perf::PerfStats>,
+    path: String,
+) -> Result<minigames::MinigameRecords, AppError> {
+    perf.time("parse_minigame_records", || {
+        let sections = load_sections(&app, &path)?;
+        let game = resolve_game(&app, &sections)?;
+        Ok(minigames::parse_minigame_records(
+            &sections,
+            game.profile(),
+        )?)
+    })
+}
+
+/// Convert a single party Pokemon file (e.g. a .pk3) between the vanilla
+/// encrypted layout and CFRU's fixed layout, writing the result to `out_path`.
+#[tauri::command(async)]
+fn convert_pokemon(path: String, out_path: String, to: pk3::Format) -> Result<(), AppError> {
+    let raw = std::fs::read(&path).map_err(|e| ParseError::Io(e.to_string()))?;
+    let converted = pk3::convert(&raw, to)?;
+    std::fs::write(&out_path, converted).map_err(|e| ExportError::Write(e.to_string()))?;
+    Ok(())
+}
+
+/// Fails with an `encode` error when `nickname` can't be stored as an
+/// international Pokemon nickname, so a rename can be checked as it's typed.
+#[tauri::command]
+fn check_nickname(nickname: String) -> Result<(), AppError> {
+    charmap::encode_gen3_string(&nickname, charmap::NICKNAME_LEN)?;
+    Ok(())
+}
+
+/// Emulator folders only exist on desktop; phones pick saves by hand.
+#[tauri::command]
+fn detect_emulators(app: AppHandle) -> Vec<setup::Emulator> {
+    if cfg!(mobile) {
+        return Vec::new();
+    }
+    app.path()
+        .config_dir()
+        .map(|dir| setup::detect_emulators(&dir))
+        .unwrap_or_default()
+}
+
+/// Saves found in detected emulator folders and the user's documents,
+/// downloads and desktop, for the setup wizard to offer.
+#[tauri::command]
+fn suggest_save_paths(app: AppHandle) -> Vec<String> {
+    if cfg!(mobile) {
+        return Vec::new();
+    }
+    let paths = app.path();
+    let mut roots: Vec<_> = detect_emulators(app.clone())
+        .into_iter()
+        .map(|e| e.dir.into())
+        .collect();
+    let user_dirs = [
+        paths.document_dir(),
+        paths.download_dir(),
+        paths.desktop_dir(),
+    ];
+    roots.extend(user_dirs.into_iter().flatten());
+    setup::suggest_save_paths(&roots)
+}
+
+/// Check a save picked in the setup wizard without remembering it.
+#[tauri::command(async)]
+fn validate_selection(app: AppHandle, path: String) -> Result<Vec<parser::Pokemon>, AppError> {
+    Ok(setup::validate_selection(
+        &path,
+        settings::get_io_mode(&app),
+        settings::get_game(&app),
+    )?)
+}
+
+#[tauri::command(async)]
+fn replay_corpus(dir: String) -> Result<corpus::CorpusReport, AppError> {
+    Ok(corpus::replay_corpus(&dir)?)
+}
+
+/// End-to-end check of the packaged app. Not exposed in the UI.
+#[tauri::command]
+fn self_test() -> selftest::SelfTestReport {
+    selftest::self_test(&std::env::temp_dir())
+}
+
+#[tauri::command]
+fn get_perf_stats(perf: State<perf::PerfStats>) -> HashMap<String, perf::CommandStats> {
+    perf.snapshot()
 }
 
 #[tauri::command]
@@ -16,12 +574,139 @@ fn get_saved_path(app: AppHandle) -> Option<String> {
     settings::get_saved_path(&app)
 }
 
+#[tauri::command]
+fn get_clean_paste(app: AppHandle) -> bool {
+    settings::get_clean_paste(&app)
+}
+
+#[tauri::command]
+fn set_clean_paste(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    settings::set_clean_paste(&app, enabled)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_own_catches_only(app: AppHandle) -> bool {
+    settings::get_own_catches_only(&app)
+}
+
+#[tauri::command]
+fn set_own_catches_only(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    settings::set_own_catches_only(&app, enabled)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_io_mode(app: AppHandle) -> reader::IoMode {
+    settings::get_io_mode(&app)
+}
+
+#[tauri::command]
+fn set_io_mode(app: AppHandle, mode: reader::IoMode) -> Result<(), AppError> {
+    settings::set_io_mode(&app, mode)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_game(app: AppHandle) -> Option<profile::Game> {
+    settings::get_game(&app)
+}
+
+#[tauri::command]
+fn set_game(app: AppHandle, game: Option<profile::Game>) -> Result<(), AppError> {
+    settings::set_game(&app, game)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_rules(app: AppHandle) -> Vec<rules::Rule> {
+    settings::get_rules(&app)
+}
+
+#[tauri::command]
+fn set_rules(app: AppHandle, rules: Vec<rules::Rule>) -> Result<(), AppError> {
+    settings::set_rules(&app, rules)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_archive_dir(app: AppHandle) -> Option<String> {
+    settings::get_archive_dir(&app)
+}
+
+#[tauri::command]
+fn set_archive_dir(app: AppHandle, dir: Option<String>) -> Result<(), AppError> {
+    settings::set_archive_dir(&app, dir)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_level_caps(app: AppHandle) -> Vec<u8> {
+    settings::get_level_caps(&app)
+}
+
+#[tauri::command]
+fn set_level_caps(app: AppHandle, level_caps: Vec<u8>) -> Result<(), AppError> {
+    settings::set_level_caps(&app, level_caps)?;
+    Ok(())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![parse_sav_file, get_saved_path])
+        .manage(perf::PerfStats::default())
+        .manage(session::SaveState::default())
+        .manage(session::ParseCache::default())
+        .invoke_handler(tauri::generate_handler![
+            parse_sav_file,
+            parse_sav_bytes,
+            load_demo,
+            get_current_save,
+            team_readiness,
+            export_facility_team,
+            project_stats,
+            parse_boxes,
+            list_boxed_pokemon,
+            export_selected,
+            export_box,
+            compare_box_report,
+            preview_release,
+            preview_move,
+            parse_daycare,
+            parse_trainer_info,
+            parse_bag,
+            story_progress,
+            get_dex_progress,
+            parse_hall_of_fame,
+            parse_mail,
+            parse_rematch_state,
+            parse_minigame_records,
+            convert_pokemon,
+            check_nickname,
+            detect_emulators,
+            suggest_save_paths,
+            validate_selection,
+            replay_corpus,
+            self_test,
+            get_perf_stats,
+            get_saved_path,
+            get_clean_paste,
+            set_clean_paste,
+            get_own_catches_only,
+            set_own_catches_only,
+            get_io_mode,
+            set_io_mode,
+            get_game,
+            set_game,
+            get_rules,
+            set_rules,
+            get_archive_dir,
+            set_archive_dir,
+            get_level_caps,
+            set_level_caps
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,98 @@
+use serde::Serialize;
+
+use crate::charmap::{decode_gen3_string_in, Charset};
+use crate::data;
+use crate::error::ParseError;
+use crate::parser::{self, bytes_at, u16_le, Section};
+use crate::profile::GameProfile;
+
+const MAIL_SIZE: usize = 36;
+/// Slots 0-5 hold mail carried by party members, 6-15 are the PC mailbox.
+const MAIL_COUNT: usize = 16;
+const PARTY_MAIL_SLOTS: usize = 6;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Mail {
+    pub slot: usize,
+    pub in_mailbox: bool,
+    pub author: String,
+    pub author_tid: u16,
+    pub species: String,
+    pub item: String,
+    /// Easy Chat word IDs making up the message, 0xFFFF marks an unused word.
+    pub words: Vec<u16>,
+}
+
+/// Parse a single 36-byte mail entry. Empty slots have no mail item.
+/// Layout: words(9 x u16), author name(8), author trainer ID(4), species(u16), item(u16).
+fn parse_mail_entry(slot: usize, raw: &[u8], charset: Charset) -> Option<Mail> {
+    let item_id = u16_le(raw, 32);
+    if item_id == 0 {
+        return None;
+    }
+
+    Some(Mail {
+        slot,
+        in_mailbox: slot >= PARTY_MAIL_SLOTS,
+        author: decode_gen3_string_in(&raw[18..26], charset),
+        author_tid: u16_le(raw, 26),
+        species: data::species_name(u16_le(raw, 30)).to_string(),
+        item: data::item_name(item_id).to_string(),
+        words: (0..9).map(|i| u16_le(raw, i * 2)).collect(),
+    })
+}
+
+/// Read every mail stored in the save: mail held by party members plus the PC mailbox.
+pub fn parse_mail(sections: &[Section], profile: &GameProfile) -> Result<Vec<Mail>, ParseError> {
+    let block = parser::profile_save_block1(sections, profile)?;
+    let charset = parser::save_charset(sections, profile)?;
+
+    let mut mail = Vec::new();
+    for i in 0..MAIL_COUNT {
+        let raw = bytes_at(&block, profile.mail_offset + i * MAIL_SIZE, MAIL_SIZE)?;
+        mail.extend(parse_mail_entry(i, raw, charset));
+    }
+    Ok(mail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_no_mail_in_test_save() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let mail = parse_mail(&sections, &RADICAL_RED).expect("Failed to parse mail");
+        assert!(mail.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mail_entry() {
+        let mut raw = [0xFFu8; MAIL_SIZE];
+        raw[0..2].copy_from_slice(&5u16.to_le_bytes());
+        // "Red" followed by the terminator
+        raw[18..22].copy_from_slice(&[0xCC, 0xD9, 0xD8, 0xFF]);
+        raw[26..30].copy_from_slice(&[0x39, 0x30, 0x00, 0x00]);
+        raw[30..32].copy_from_slice(&25u16.to_le_bytes());
+        raw[32..34].copy_from_slice(&121u16.to_le_bytes());
+
+        let mail =
+            parse_mail_entry(7, &raw, Charset::International).expect("Mail should be present");
+        assert!(mail.in_mailbox);
+        assert_eq!(mail.author, "Red");
+        assert_eq!(mail.author_tid, 12345);
+        assert_eq!(mail.species, "Pikachu");
+        assert_eq!(mail.item, "Orange Mail");
+        assert_eq!(mail.words[0], 5);
+        assert_eq!(mail.words[1], 0xFFFF);
+
+        // "サトシ" from a Japanese save
+        raw[18..22].copy_from_slice(&[0x5B, 0x64, 0x5C, 0xFF]);
+        let mail = parse_mail_entry(7, &raw, Charset::Japanese).expect("Mail should be present");
+        assert_eq!(mail.author, "サトシ");
+    }
+}
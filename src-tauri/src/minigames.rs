@@ -0,0 +1,111 @@
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::parser::{self, u16_le, u32_le, Section};
+use crate::profile::GameProfile;
+
+/// Offsets of each minigame's records from the start of the Berry Crush ones.
+const POKEMON_JUMP_OFFSET: usize = 0x10;
+const BERRY_PICKING_OFFSET: usize = 0x20;
+/// The records block is 0x30 bytes.
+const RECORDS_SIZE: usize = 0x30;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MinigameRecords {
+    /// Best Berry Crush pressing speed for 2, 3, 4 and 5 players, in hundredths of RPM.
+    pub berry_crush_best_speeds: [u16; 4],
+    pub berry_powder: u32,
+    pub pokemon_jump_best_score: u32,
+    pub pokemon_jump_jumps_in_row: u16,
+    pub pokemon_jump_excellents_in_row: u16,
+    pub dodrio_best_score: u32,
+    pub dodrio_berries_picked: u16,
+    pub dodrio_berries_in_row: u16,
+}
+
+pub fn parse_minigame_records(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<MinigameRecords, ParseError> {
+    let offset = profile.minigames_offset.ok_or(ParseError::Unsupported {
+        feature: "minigame records",
+    })?;
+    let sec0 = parser::find_section(sections, profile.trainer_section)?;
+    let key = parser::read_u32(sec0, profile.security_key_offset)?;
+    let records = parser::bytes_at(sec0, offset, RECORDS_SIZE)?;
+
+    let mut berry_crush_best_speeds = [0u16; 4];
+    for (i, speed) in berry_crush_best_speeds.iter_mut().enumerate() {
+        *speed = u16_le(records, i * 2);
+    }
+
+    Ok(MinigameRecords {
+        berry_crush_best_speeds,
+        berry_powder: u32_le(records, 8) ^ key,
+        pokemon_jump_jumps_in_row: u16_le(records, POKEMON_JUMP_OFFSET),
+        pokemon_jump_excellents_in_row: u16_le(records, POKEMON_JUMP_OFFSET + 4),
+        pokemon_jump_best_score: u32_le(records, POKEMON_JUMP_OFFSET + 12),
+        dodrio_best_score: u32_le(records, BERRY_PICKING_OFFSET),
+        dodrio_berries_picked: u16_le(records, BERRY_PICKING_OFFSET + 4),
+        dodrio_berries_in_row: u16_le(records, BERRY_PICKING_OFFSET + 6),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::{section_mut, TEST_SAV};
+    use crate::profile::{EMERALD, RADICAL_RED};
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_no_minigame_records() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let records =
+            parse_minigame_records(&sections, &RADICAL_RED).expect("Failed to parse records");
+        assert_eq!(records.berry_crush_best_speeds, [0; 4]);
+        assert_eq!(records.berry_powder, 0);
+        assert_eq!(records.pokemon_jump_best_score, 0);
+        assert_eq!(records.dodrio_best_score, 0);
+
+        assert!(matches!(
+            parse_minigame_records(&sections, &EMERALD),
+            Err(ParseError::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_minigame_records() {
+        let mut sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        assert_eq!(RADICAL_RED.minigames_offset, Some(0xAF0));
+        let sec0 = section_mut(&mut sections, RADICAL_RED.trainer_section);
+        // The sample save's key is 0, which would hide a missing XOR
+        let key = 0x1357_9BDFu32;
+        let key_offset = RADICAL_RED.security_key_offset;
+        sec0[key_offset..key_offset + 4].copy_from_slice(&key.to_le_bytes());
+        let records = &mut sec0[0xAF0..0xAF0 + RECORDS_SIZE];
+        for (i, speed) in [850u16, 1020, 1175, 1310].iter().enumerate() {
+            records[i * 2..i * 2 + 2].copy_from_slice(&speed.to_le_bytes());
+        }
+        records[8..12].copy_from_slice(&(1234u32 ^ key).to_le_bytes());
+        records[0x10..0x12].copy_from_slice(&57u16.to_le_bytes());
+        records[0x14..0x16].copy_from_slice(&12u16.to_le_bytes());
+        records[0x1C..0x20].copy_from_slice(&4321u32.to_le_bytes());
+        records[0x20..0x24].copy_from_slice(&9876u32.to_le_bytes());
+        records[0x24..0x26].copy_from_slice(&321u16.to_le_bytes());
+        records[0x26..0x28].copy_from_slice(&45u16.to_le_bytes());
+
+        let records =
+            parse_minigame_records(&sections, &RADICAL_RED).expect("Failed to parse records");
+        assert_eq!(records.berry_crush_best_speeds, [850, 1020, 1175, 1310]);
+        assert_eq!(records.berry_powder, 1234);
+        assert_eq!(records.pokemon_jump_jumps_in_row, 57);
+        assert_eq!(records.pokemon_jump_excellents_in_row, 12);
+        assert_eq!(records.pokemon_jump_best_score, 4321);
+        assert_eq!(records.dodrio_best_score, 9876);
+        assert_eq!(records.dodrio_berries_picked, 321);
+        assert_eq!(records.dodrio_berries_in_row, 45);
+    }
+}
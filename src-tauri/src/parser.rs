@@ -1,15 +1,79 @@
-use serde::Serialize;
-use std::fs;
+use serde::{Deserialize, Serialize};
 
-use crate::charmap::decode_gen3_string;
+use crate::charmap::{decode_gen3_string, decode_gen3_string_in, Charset};
 use crate::data;
+use crate::error::ParseError;
+use crate::mail::{self, Mail};
+use crate::pk3::{self, Format};
+use crate::profile::{Game, GameProfile, PokemonLayout, RADICAL_RED};
+use crate::reader::{self, IoMode};
 
-const SECTION_SIZE: usize = 0x1000;
-const SECTION_COUNT: usize = 14;
-const SLOT_SIZE: usize = SECTION_SIZE * SECTION_COUNT;
+pub(crate) const SECTION_SIZE: usize = 0x1000;
+pub(crate) const SECTION_COUNT: usize = 14;
+pub(crate) const SLOT_SIZE: usize = SECTION_SIZE * SECTION_COUNT;
+/// Section footer: id(u16) at 0xFF4, checksum(u16) at 0xFF6, signature, save index.
+pub(crate) const SECTION_CHECKSUM_OFFSET: usize = 0xFF6;
+const SECTION_SIGNATURE_OFFSET: usize = 0xFF8;
+/// Written into every section footer when the game saves.
+pub(crate) const SECTION_SIGNATURE: u32 = 0x0801_2025;
 
-const PARTY_OFFSET: usize = 0x0038;
-const POKEMON_SIZE: usize = 100;
+/// PC storage is split across its sections in chunks of this size: a u32
+/// current box, then the boxes back to back, then the box names.
+const PC_CHUNK: usize = 0xF80;
+
+/// Section 0 game code: 1 for FireRed/LeafGreen and hacks built on them, 0 for
+/// Ruby/Sapphire. Emerald keeps its random security key here instead.
+const GAME_CODE_OFFSET: usize = 0xAC;
+const GAME_CODE_FRLG: u32 = 1;
+const GAME_CODE_RUBY_SAPPHIRE: u32 = 0;
+
+/// A Pokemon is shiny when TID ^ SID ^ PID high ^ PID low is below this.
+const SHINY_THRESHOLD: u32 = 8;
+
+/// Each egg cycle is 256 steps; the remaining cycle count is kept in the friendship byte.
+const STEPS_PER_EGG_CYCLE: u32 = 256;
+
+/// Stat names in Showdown order, which is also the order of `Pokemon::ivs`.
+const STAT_NAMES: [&str; 6] = ["HP", "Atk", "Def", "SpA", "SpD", "Spe"];
+/// Bit position of each IV in the IV word, in Showdown stat order.
+/// The game packs them as HP, Atk, Def, Spe, SpA, SpD, 5 bits each.
+const IV_SHIFTS: [u32; 6] = [0, 5, 10, 20, 25, 15];
+const MAX_IV: u8 = 31;
+/// Display text for eggs, which have no usable set to export.
+const EGG_DISPLAY_TEXT: &str = "Egg (species hidden)";
+/// Display text for Bad Eggs, whose data is garbage.
+const BAD_EGG_DISPLAY_TEXT: &str = "Bad Egg";
+/// Header flags byte: bit 0 is set by the game on Bad Eggs.
+const FLAGS_OFFSET: usize = 19;
+const BAD_EGG_FLAG: u8 = 1;
+/// Showdown assumes max friendship unless a set says otherwise.
+const MAX_FRIENDSHIP: u8 = 255;
+/// Position of each EV in the EV substructure, in Showdown stat order.
+/// The substructure stores them as HP, Atk, Def, Spe, SpA, SpD.
+const EV_ORDER: [usize; 6] = [0, 1, 2, 4, 5, 3];
+
+/// Hidden Power types, indexed by the type value derived from the IVs.
+const HIDDEN_POWER_TYPES: [&str; 16] = [
+    "Fighting", "Flying", "Poison", "Ground", "Rock", "Bug", "Ghost", "Steel",
+    "Fire", "Water", "Grass", "Electric", "Psychic", "Ice", "Dragon", "Dark",
+];
+/// Weight of each IV in the Hidden Power sums, in Showdown stat order. The
+/// game weighs them in its own HP, Atk, Def, Spe, SpA, SpD order.
+const HIDDEN_POWER_WEIGHTS: [u32; 6] = [1, 2, 4, 16, 32, 8];
+
+/// The game's Atk, Def, Spe, SpA, SpD order as positions in Showdown stat order.
+/// Natures and the stats stored after max HP in a party struct both use it.
+const GAME_STAT_ORDER: [usize; 5] = [1, 2, 5, 3, 4];
+
+/// Contest ribbon categories, each a 3-bit rank at the bottom of the ribbon word.
+const CONTEST_RIBBONS: [&str; 5] = ["Cool", "Beauty", "Cute", "Smart", "Tough"];
+/// Ribbon names for each contest rank; a rank includes the ribbons below it.
+const CONTEST_RIBBON_RANKS: [&str; 4] = ["", " Super", " Hyper", " Master"];
+/// One-bit ribbons, from bit 15 of the ribbon word up.
+const RIBBON_FLAGS: [&str; 12] = [
+    "Champion", "Winning", "Victory", "Artist", "Effort", "Marine",
+    "Land", "Sky", "Country", "National", "Earth", "World",
+];
 
 const NATURES: [&str; 25] = [
     "Hardy", "Lonely", "Brave", "Adamant", "Naughty",
@@ -19,95 +83,659 @@ const NATURES: [&str; 25] = [
     "Calm", "Gentle", "Sassy", "Careful", "Quirky",
 ];
 
-fn u16_le(data: &[u8], off: usize) -> u16 {
+/// Little-endian reads for buffers whose size is fixed by a layout, such as a
+/// decoded Pokemon. Save data of unknown size goes through `read_u16`/`read_u32`.
+pub(crate) fn u16_le(data: &[u8], off: usize) -> u16 {
     u16::from_le_bytes([data[off], data[off + 1]])
 }
 
-fn u32_le(data: &[u8], off: usize) -> u32 {
+pub(crate) fn u32_le(data: &[u8], off: usize) -> u32 {
     u32::from_le_bytes([data[off], data[off + 1], data[off + 2], data[off + 3]])
 }
 
+/// `len` bytes of `data` from `off`, or an error if they run past the end.
+pub(crate) fn bytes_at(data: &[u8], off: usize, len: usize) -> Result<&[u8], ParseError> {
+    off.checked_add(len)
+        .and_then(|end| data.get(off..end))
+        .ok_or(ParseError::OutOfBounds {
+            offset: off,
+            len,
+            size: data.len(),
+        })
+}
+
+pub(crate) fn read_u16(data: &[u8], off: usize) -> Result<u16, ParseError> {
+    bytes_at(data, off, 2).map(|b| u16_le(b, 0))
+}
+
+pub(crate) fn read_u32(data: &[u8], off: usize) -> Result<u32, ParseError> {
+    bytes_at(data, off, 4).map(|b| u32_le(b, 0))
+}
+
+/// Where a Pokemon is kept in the save. Slots are 0-based; box numbers start
+/// at 1 like `PcBox::number`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Location {
+    Party { slot: usize },
+    Box { box_number: usize, slot: usize },
+    Daycare { slot: usize },
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct Pokemon {
+    pub location: Location,
     pub nickname: String,
     pub species: String,
+    /// National dex number of the base species, None for RR-only species.
+    pub dex_number: Option<u16>,
+    pub generation: Option<u8>,
+    /// None when the species' gender ratio is unknown.
+    pub gender: Option<Gender>,
+    pub is_shiny: bool,
+    pub markings: Markings,
+    /// The original trainer. None when left out with `ParseOptions::include_ot`.
+    pub ot_name: Option<String>,
+    pub tid: Option<u16>,
+    pub sid: Option<u16>,
     pub level: u8,
+    pub exp: u32,
+    /// Experience left until the next level. None at level 100 or when the
+    /// species' growth rate is unknown.
+    pub exp_to_next_level: Option<u32>,
+    /// None when the location ID isn't in the location table.
+    pub met_location: Option<String>,
+    /// 0 for Pokemon hatched from eggs.
+    pub met_level: u8,
+    pub origin_game: Option<OriginGame>,
+    /// For eggs this byte counts egg cycles instead; see `egg_steps_to_hatch`.
+    pub friendship: u8,
+    /// HP and status are only stored for party members; None for boxed Pokemon.
+    pub current_hp: Option<u16>,
+    pub max_hp: Option<u16>,
+    pub status: Option<Status>,
     pub item: Option<String>,
+    /// The mail a party member holds. Boxed Pokemon can't hold mail.
+    pub mail: Option<Mail>,
+    /// None when the ball ID isn't in the ball table.
+    pub ball: Option<String>,
     pub nature: String,
     pub ability: String,
-    pub moves: Vec<String>,
+    pub moves: Vec<Move>,
+    /// HP, Atk, Def, SpA, SpD, Spe.
+    pub evs: [u8; 6],
+    /// HP, Atk, Def, SpA, SpD, Spe. None when left out with
+    /// `ParseOptions::include_ivs`.
+    pub ivs: Option<[u8; 6]>,
+    pub hidden_power: HiddenPower,
+    /// HP, Atk, Def, SpA, SpD, Spe. Party members use the stats stored in the
+    /// save; boxed Pokemon get them worked out from base stats, and None when
+    /// the species' base stats aren't known to match the game's.
+    pub stats: Option<[u16; 6]>,
+    /// Only stored for party members; None for boxed Pokemon.
+    pub contest_stats: Option<ContestStats>,
+    /// CFRU drops ribbons from boxed Pokemon, so theirs are always empty.
+    pub ribbons: Vec<String>,
+    /// True when the OT ID or OT name differs from the save's trainer.
+    pub is_traded: bool,
+    pub is_egg: bool,
+    /// Corrupt data the game would show as a Bad Egg. Bad Eggs also count as eggs.
+    pub is_bad_egg: bool,
+    /// Estimated steps until an egg hatches, None for hatched Pokemon and Bad Eggs.
+    pub egg_steps_to_hatch: Option<u32>,
+    /// Moves with no known name, by move slot (0-3), so they can be fixed up by hand.
+    pub unknown_moves: Vec<UnknownMove>,
+    /// Problems with the struct's data, so corrupt entries can be told apart.
+    pub parse_warnings: Vec<ParseWarning>,
     pub display_text: String,
 }
 
-struct Section {
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Gender {
+    Male,
+    Female,
+    Genderless,
+}
+
+impl Gender {
+    /// Gender from the PID's low byte and the species' gender threshold.
+    fn from_personality(personality: u32, ratio: u8) -> Gender {
+        match ratio {
+            data::MON_MALE => Gender::Male,
+            data::MON_FEMALE => Gender::Female,
+            data::MON_GENDERLESS => Gender::Genderless,
+            _ if ((personality & 0xFF) as u8) < ratio => Gender::Female,
+            _ => Gender::Male,
+        }
+    }
+
+    /// Showdown's gender marker, if the species has one.
+    fn marker(self) -> Option<&'static str> {
+        match self {
+            Gender::Male => Some("M"),
+            Gender::Female => Some("F"),
+            Gender::Genderless => None,
+        }
+    }
+}
+
+/// Hidden Power as it works before Gen 6, where the power also depends on IVs.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct HiddenPower {
+    pub type_name: String,
+    pub power: u8,
+}
+
+impl HiddenPower {
+    /// The type comes from the lowest bit of each IV, the power from the second lowest.
+    fn from_ivs(ivs: &[u8; 6]) -> HiddenPower {
+        let sum = |bit: u8| -> u32 {
+            ivs.iter()
+                .zip(HIDDEN_POWER_WEIGHTS)
+                .map(|(&iv, weight)| ((iv >> bit) & 1) as u32 * weight)
+                .sum()
+        };
+        HiddenPower {
+            type_name: HIDDEN_POWER_TYPES[(sum(0) * 15 / 63) as usize].to_string(),
+            power: (sum(1) * 40 / 63 + 30) as u8,
+        }
+    }
+}
+
+/// Contest condition, stored after the EVs in the EV substructure.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct ContestStats {
+    pub cool: u8,
+    pub beauty: u8,
+    pub cute: u8,
+    pub smart: u8,
+    pub tough: u8,
+    pub sheen: u8,
+}
+
+impl ContestStats {
+    fn from_bytes(bytes: &[u8]) -> ContestStats {
+        ContestStats {
+            cool: bytes[0],
+            beauty: bytes[1],
+            cute: bytes[2],
+            smart: bytes[3],
+            tough: bytes[4],
+            sheen: bytes[5],
+        }
+    }
+}
+
+/// Game a Pokemon was caught or hatched in, from the origins word.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginGame {
+    Sapphire,
+    Ruby,
+    Emerald,
+    FireRed,
+    LeafGreen,
+    Colosseum,
+}
+
+impl OriginGame {
+    fn from_id(id: u16) -> Option<OriginGame> {
+        match id {
+            1 => Some(OriginGame::Sapphire),
+            2 => Some(OriginGame::Ruby),
+            3 => Some(OriginGame::Emerald),
+            4 => Some(OriginGame::FireRed),
+            5 => Some(OriginGame::LeafGreen),
+            15 => Some(OriginGame::Colosseum),
+            _ => None,
+        }
+    }
+}
+
+/// The box markings the player set in game.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Markings {
+    pub circle: bool,
+    pub square: bool,
+    pub triangle: bool,
+    pub heart: bool,
+}
+
+impl Markings {
+    /// One bit per marking, circle first.
+    fn from_byte(byte: u8) -> Markings {
+        Markings {
+            circle: byte & 1 != 0,
+            square: byte & (1 << 1) != 0,
+            triangle: byte & (1 << 2) != 0,
+            heart: byte & (1 << 3) != 0,
+        }
+    }
+}
+
+/// Non-volatile status condition of a party Pokemon.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Healthy,
+    Sleep,
+    Poison,
+    Burn,
+    Freeze,
+    Paralysis,
+    Toxic,
+}
+
+impl Status {
+    /// Decode the status word: bits 0-2 are sleep turns, then one bit each for
+    /// poison, burn, freeze, paralysis and toxic.
+    fn from_word(word: u32) -> Status {
+        if word & 0x7 != 0 {
+            Status::Sleep
+        } else if word & (1 << 3) != 0 {
+            Status::Poison
+        } else if word & (1 << 4) != 0 {
+            Status::Burn
+        } else if word & (1 << 5) != 0 {
+            Status::Freeze
+        } else if word & (1 << 6) != 0 {
+            Status::Paralysis
+        } else if word & (1 << 7) != 0 {
+            Status::Toxic
+        } else {
+            Status::Healthy
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PcBox {
+    /// 1-based box number, as shown in game.
+    pub number: usize,
+    pub pokemon: Vec<Pokemon>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Move {
+    pub name: String,
+    /// Current PP. Only stored for party members; None for boxed Pokemon.
+    pub pp: Option<u8>,
+    /// Max PP after PP Ups. None while there is no base PP table for the move.
+    pub max_pp: Option<u8>,
+    /// PP Ups applied to this move, 0-3.
+    pub pp_ups: u8,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParseWarning {
+    /// The checksum failed or the game flagged the struct as a Bad Egg.
+    BadEgg,
+    /// The species ID isn't in the species table.
+    UnknownSpecies { id: u16 },
+    /// Stats weren't calculated, as the species' base stats aren't known to
+    /// match the game's.
+    UnverifiedBaseStats,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct UnknownMove {
+    pub slot: usize,
+    pub id: u16,
+}
+
+pub(crate) struct Section {
     id: u16,
     save_index: u32,
     data: Vec<u8>,
 }
 
-fn parse_save_slot(raw: &[u8], slot_offset: usize) -> Vec<Section> {
+/// Every section of a slot is SECTION_SIZE bytes, so fixed offsets inside a
+/// section's data are always in bounds.
+fn parse_save_slot(raw: &[u8], slot_offset: usize) -> Result<Vec<Section>, ParseError> {
     (0..SECTION_COUNT)
         .map(|i| {
-            let start = slot_offset + i * SECTION_SIZE;
-            let data = raw[start..start + SECTION_SIZE].to_vec();
-            Section {
+            let data = bytes_at(raw, slot_offset + i * SECTION_SIZE, SECTION_SIZE)?.to_vec();
+            Ok(Section {
                 id: u16_le(&data, 0xFF4),
                 save_index: u32_le(&data, 0xFFC),
                 data,
-            }
+            })
         })
         .collect()
 }
 
-fn get_active_slot(raw: &[u8]) -> Vec<Section> {
-    let a = parse_save_slot(raw, 0);
-    let b = parse_save_slot(raw, SLOT_SIZE);
-    if a[0].save_index >= b[0].save_index { a } else { b }
+/// Whether a slot holds a completed save: every section signed, with a save
+/// index, and each section ID present once. A new game only writes one slot and
+/// leaves the other 0xFF-filled.
+fn is_written_slot(slot: &[Section]) -> bool {
+    let mut seen = [false; SECTION_COUNT];
+    slot.iter().all(|s| {
+        u32_le(&s.data, SECTION_SIGNATURE_OFFSET) == SECTION_SIGNATURE
+            && s.save_index != u32::MAX
+            && seen
+                .get_mut(s.id as usize)
+                .is_some_and(|seen| !std::mem::replace(seen, true))
+    })
+}
+
+fn get_active_slot(raw: &[u8]) -> Result<Vec<Section>, ParseError> {
+    let a = parse_save_slot(raw, 0)?;
+    // A 64KiB image only has room for slot A
+    let b = if raw.len() >= SLOT_SIZE * 2 {
+        parse_save_slot(raw, SLOT_SIZE)?
+    } else {
+        Vec::new()
+    };
+    match (is_written_slot(&a), !b.is_empty() && is_written_slot(&b)) {
+        (true, true) if a[0].save_index >= b[0].save_index => Ok(a),
+        (true, true) | (false, true) => Ok(b),
+        (true, false) => Ok(a),
+        (false, false) => Err(ParseError::NoCompletedSave),
+    }
+}
+
+/// Section checksum: the u32 words of `data` summed, then both halves folded
+/// together.
+pub(crate) fn section_checksum(data: &[u8]) -> u16 {
+    let sum = data
+        .chunks_exact(4)
+        .fold(0u32, |sum, word| sum.wrapping_add(u32_le(word, 0)));
+    ((sum >> 16) as u16).wrapping_add(sum as u16)
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CorruptSection {
+    pub id: u16,
+    /// Checksum stored in the section footer.
+    pub expected: u16,
+    /// Checksum of the section's data as read.
+    pub actual: u16,
+}
+
+/// Sections of the active slot whose data doesn't match their stored checksum.
+/// Anything parsed from these may be garbage.
+pub(crate) fn corrupt_sections(sections: &[Section], profile: &GameProfile) -> Vec<CorruptSection> {
+    sections
+        .iter()
+        .filter_map(|section| {
+            let size = *profile.section_sizes.get(section.id as usize)?;
+            let expected = u16_le(&section.data, SECTION_CHECKSUM_OFFSET);
+            let actual = section_checksum(section.data.get(..size)?);
+            (actual != expected).then_some(CorruptSection {
+                id: section.id,
+                expected,
+                actual,
+            })
+        })
+        .collect()
 }
 
-fn find_section(sections: &[Section], id: u16) -> Result<&[u8], String> {
+pub(crate) fn find_section(sections: &[Section], id: u16) -> Result<&[u8], ParseError> {
     sections
         .iter()
         .find(|s| s.id == id)
         .map(|s| s.data.as_slice())
-        .ok_or_else(|| format!("Section {} not found", id))
+        .ok_or(ParseError::MissingSection { id })
+}
+
+/// The save owner's name and full OT ID (TID | SID << 16) from section 0.
+struct Trainer {
+    name: String,
+    ot_id: u32,
+}
+
+impl Trainer {
+    fn from_sections(sections: &[Section], profile: &GameProfile) -> Result<Trainer, ParseError> {
+        let sec0 = find_section(sections, profile.trainer_section)?;
+        Ok(Trainer {
+            name: decode_gen3_string(bytes_at(sec0, 0, 8)?),
+            ot_id: read_u32(sec0, 0x0A)?,
+        })
+    }
+
+    /// Whether this trainer is the original trainer of a raw Pokemon struct.
+    fn is_ot_of(&self, pkmn: &[u8]) -> bool {
+        u32_le(pkmn, 4) == self.ot_id && decode_gen3_string(&pkmn[20..27]) == self.name
+    }
+}
+
+/// The character table the save's own text is written in. Saves don't record
+/// their language, so it's taken from the first party Pokemon the trainer
+/// caught. Eggs are skipped, as the games mark every egg as Japanese until
+/// it hatches.
+pub(crate) fn save_charset(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<Charset, ParseError> {
+    let ot_id = read_u32(find_section(sections, profile.trainer_section)?, 0x0A)?;
+    let sec = find_section(sections, profile.party_section)?;
+    let party_count = (read_u32(sec, profile.party_count_offset)? as usize).min(6);
+    let size = profile.pokemon.size;
+    for i in 0..party_count {
+        let pkmn = decode_pokemon(bytes_at(sec, profile.party_offset + i * size, size)?, profile);
+        let caught = parse_pokemon(&pkmn, &profile.pokemon).is_some_and(|m| !m.is_egg);
+        if caught && u32_le(&pkmn, 4) == ot_id {
+            return Ok(Charset::from_language(pkmn[18]));
+        }
+    }
+    Ok(Charset::International)
+}
+
+/// Reassemble SaveBlock1 from sections 1-4 so its fields can be read at their
+/// in-memory offsets. CFRU extends sections 1-3 to 0xFF0
+/// bytes and its SaveBlock1 runs on into the extra space, so offsets past the
+/// first section only line up when each section is read to its checksummed size.
+pub(crate) fn profile_save_block1(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<Vec<u8>, ParseError> {
+    let mut block = Vec::new();
+    for id in 1..=4 {
+        let size = profile.section_sizes[id as usize];
+        block.extend_from_slice(bytes_at(find_section(sections, id)?, 0, size)?);
+    }
+    Ok(block)
+}
+
+/// Split a raw save image into the sections of its most recent save slot.
+/// Accepts 64KiB images holding one slot, and ignores anything past the two
+/// slots, such as the Hall of Fame sectors or RTC data emulators append.
+pub(crate) fn active_slot(raw: &[u8]) -> Result<Vec<Section>, ParseError> {
+    if raw.len() < SLOT_SIZE {
+        return Err(ParseError::TooSmall { len: raw.len() });
+    }
+
+    get_active_slot(raw)
+}
+
+/// Read a .sav file and return the sections of its most recent save slot.
+pub(crate) fn load_active_slot(path: &str, io_mode: IoMode) -> Result<Vec<Section>, ParseError> {
+    let raw = reader::read_save(path, io_mode)?;
+    active_slot(&raw)
 }
 
-/// Parse a single party Pokemon from raw bytes (100 bytes).
-/// CFRU/Radical Red uses fixed substructure order and no XOR encryption:
+/// Expand a compressed CFRU box Pokemon (58 bytes) into the party `layout` so
+/// it can go through `parse_pokemon`. Boxed Pokemon store no level, PP,
+/// contest stats or battle stats; the level is worked out from experience.
+/// Box layout: header(28), species(u16), item(u16), exp(u32), ppBonuses,
+/// friendship, ball, moves(4 x 10 bits), EVs(6), pokerus, metLocation,
+/// origins(u16), IV word(u32).
+fn expand_box_pokemon(boxed: &[u8], layout: &PokemonLayout) -> Vec<u8> {
+    let mut pkmn = vec![0u8; layout.size];
+    pkmn[0..28].copy_from_slice(&boxed[0..28]);
+    pkmn[layout.growth..layout.growth + 11].copy_from_slice(&boxed[28..39]);
+
+    let packed_moves = boxed[39..44]
+        .iter()
+        .rev()
+        .fold(0u64, |acc, &b| (acc << 8) | b as u64);
+    for slot in 0..4 {
+        let id = ((packed_moves >> (slot * 10)) & 0x3FF) as u16;
+        let off = layout.attacks + slot * 2;
+        pkmn[off..off + 2].copy_from_slice(&id.to_le_bytes());
+    }
+
+    pkmn[layout.evs..layout.evs + 6].copy_from_slice(&boxed[44..50]);
+    pkmn[layout.misc..layout.misc + 8].copy_from_slice(&boxed[50..58]);
+    set_level_from_exp(&mut pkmn, layout);
+    pkmn
+}
+
+/// Work out the level of a boxed Pokemon, which isn't stored, from its experience.
+fn set_level_from_exp(pkmn: &mut [u8], layout: &PokemonLayout) {
+    // Species outside the growth tables fall back to Medium Fast, the most common rate.
+    let species = data::species_name(u16_le(pkmn, layout.growth));
+    let growth = data::growth_rate(species).unwrap_or(data::GrowthRate::MediumFast);
+    pkmn[layout.level] = growth.level_for_exp(u32_le(pkmn, layout.growth + 4));
+}
+
+/// Bring a stored Pokemon into the unencrypted party `layout`. Vanilla structs
+/// are decrypted and un-shuffled; ones whose checksum fails get the Bad Egg
+/// flag, as the game would give them. CFRU only compresses the Pokemon in PC
+/// storage; ones kept elsewhere, like the daycare, are the full box struct.
+fn decode_pokemon(raw: &[u8], profile: &GameProfile) -> Vec<u8> {
+    let layout = &profile.pokemon;
+    let boxed = raw.len() < layout.size;
+    match profile.pokemon_format {
+        Format::Cfru if raw.len() == profile.box_pokemon_size => expand_box_pokemon(raw, layout),
+        Format::Cfru if boxed => {
+            let mut pkmn = raw.to_vec();
+            pkmn.resize(layout.size, 0);
+            set_level_from_exp(&mut pkmn, layout);
+            pkmn
+        }
+        Format::Cfru => raw.to_vec(),
+        Format::Vanilla => {
+            let mut padded = raw.to_vec();
+            padded.resize(layout.size, 0);
+            let mut pkmn = match pk3::vanilla_to_cfru(&padded) {
+                Ok(pkmn) => pkmn,
+                Err(_) => {
+                    // decrypt leaves the struct decrypted even when the checksum fails
+                    let mut garbled = padded;
+                    let _ = pk3::decrypt(&mut garbled);
+                    garbled[FLAGS_OFFSET] |= BAD_EGG_FLAG;
+                    garbled
+                }
+            };
+            if boxed {
+                set_level_from_exp(&mut pkmn, layout);
+            }
+            pkmn
+        }
+    }
+}
+
+/// Parse a single party Pokemon from raw bytes laid out as `layout`.
+/// Expects CFRU's fixed substructure order with no XOR encryption (see
+/// `decode_pokemon` for vanilla structs):
 ///   Growth(32), Attacks(44), EVs(56), Misc(68) — each 12 bytes.
-fn parse_pokemon(pkmn: &[u8]) -> Option<Pokemon> {
+fn parse_pokemon(pkmn: &[u8], layout: &PokemonLayout) -> Option<Pokemon> {
     let personality = u32_le(pkmn, 0);
     if personality == 0 {
         return None;
     }
 
-    let nickname = decode_gen3_string(&pkmn[8..18]);
-    let level = pkmn[84];
+    let ot_id = u32_le(pkmn, 4);
+    let charset = Charset::from_language(pkmn[18]);
+    let ot_name = decode_gen3_string_in(&pkmn[20..27], charset);
+    let markings = Markings::from_byte(pkmn[27]);
+    let shiny_value =
+        (ot_id >> 16) ^ (ot_id & 0xFFFF) ^ (personality >> 16) ^ (personality & 0xFFFF);
+    let is_shiny = shiny_value < SHINY_THRESHOLD;
+
+    let nickname = decode_gen3_string_in(&pkmn[8..18], charset);
+    let level = pkmn[layout.level];
     let nature_index = (personality % 25) as usize;
     let nature = NATURES[nature_index].to_string();
 
-    // Growth substructure at fixed offset 32: species(u16), item(u16)
-    let species_id = u16_le(pkmn, 32);
-    let item_id = u16_le(pkmn, 34);
+    // Growth substructure: species(u16), item(u16), exp(u32)
+    let species_id = u16_le(pkmn, layout.growth);
+    let item_id = u16_le(pkmn, layout.growth + 2);
+    let exp = u32_le(pkmn, layout.growth + 4);
+    // CFRU moves the ball out of the origins word into growth +10
+    let ball = data::ball_name(pkmn[layout.growth + 10]).map(str::to_string);
 
-    // Attacks substructure at fixed offset 44: move1-4(u16 each)
-    let moves: Vec<String> = (0..4)
-        .map(|i| u16_le(pkmn, 44 + i * 2))
-        .filter(|&m| m != 0)
-        .map(|m| data::move_name(m).to_string())
-        .collect();
+    // Attacks substructure: move1-4(u16 each); growth ppBonuses(u8 at +8) holds
+    // 2 bits of PP Ups per move slot
+    let pp_bonuses = pkmn[layout.growth + 8];
+    let mut moves = Vec::new();
+    let mut unknown_moves = Vec::new();
+    for slot in 0..4 {
+        let id = u16_le(pkmn, layout.attacks + slot * 2);
+        if id == 0 {
+            continue;
+        }
+        let name = match data::move_name(id) {
+            Some(name) => name.to_string(),
+            None => {
+                unknown_moves.push(UnknownMove { slot, id });
+                format!("Move #{}", id)
+            }
+        };
+        moves.push(Move {
+            name,
+            pp: None,
+            max_pp: None,
+            pp_ups: (pp_bonuses >> (slot * 2)) & 0x3,
+        });
+    }
+
+    let evs = EV_ORDER.map(|i| pkmn[layout.evs + i]);
 
-    // Misc substructure at fixed offset 68: iv_egg_ability(u32 at +4 = offset 72)
-    let iv_word = u32_le(pkmn, 72);
+    // Misc substructure: metLocation(u8 at +1), origins(u16 at +2): met level
+    // (bits 0-6), origin game (bits 7-10)
+    let met_location = data::location_name(pkmn[layout.misc + 1]).map(str::to_string);
+    let origins = u16_le(pkmn, layout.misc + 2);
+    let met_level = (origins & 0x7F) as u8;
+    let origin_game = OriginGame::from_id((origins >> 7) & 0xF);
+
+    // Misc substructure: iv_egg_ability(u32 at +4)
+    let iv_word = u32_le(pkmn, layout.misc + 4);
     let ability_bit = (iv_word >> 31) & 1;
+    let is_bad_egg = pkmn[FLAGS_OFFSET] & BAD_EGG_FLAG != 0;
+    // The game treats Bad Eggs as eggs, which keeps them out of battles and checks
+    let is_egg = (iv_word >> 30) & 1 == 1 || is_bad_egg;
+    let ivs = IV_SHIFTS.map(|shift| ((iv_word >> shift) & 0x1F) as u8);
+    let hidden_power = HiddenPower::from_ivs(&ivs);
+
+    // Misc substructure: ribbons(u32 at +8)
+    let ribbons = decode_ribbons(u32_le(pkmn, layout.misc + 8));
+
+    // Growth substructure friendship(u8 at +9) counts down egg cycles for eggs.
+    // A Bad Egg never hatches, so its byte means nothing.
+    let friendship = pkmn[layout.growth + 9];
+    let egg_steps_to_hatch =
+        (is_egg && !is_bad_egg).then(|| friendship as u32 * STEPS_PER_EGG_CYCLE);
+
+    let mut parse_warnings = Vec::new();
+    if is_bad_egg {
+        parse_warnings.push(ParseWarning::BadEgg);
+    }
+    if !data::is_known_species(species_id) {
+        parse_warnings.push(ParseWarning::UnknownSpecies { id: species_id });
+    }
 
     let species = data::species_name(species_id).to_string();
+    let dex_number = data::national_dex(&species);
+    let stats = data::base_stats(&species)
+        .map(|base| calc_stats(&base, &ivs, &evs, level, nature_index));
+    if stats.is_none() && data::is_known_species(species_id) {
+        parse_warnings.push(ParseWarning::UnverifiedBaseStats);
+    }
+    let exp_to_next_level = data::growth_rate(&species)
+        .filter(|_| level < 100)
+        .map(|growth| growth.exp_for_level(level + 1).saturating_sub(exp));
+    let generation = dex_number.and_then(data::generation);
+    let gender =
+        data::gender_ratio(&species).map(|ratio| Gender::from_personality(personality, ratio));
 
     // Ability slot: bit 31 set = hidden (2), else personality even = primary (0), odd = secondary (1)
     let ability_slot = if ability_bit == 1 {
         2
-    } else if personality % 2 == 0 {
+    } else if personality.is_multiple_of(2) {
         0
     } else {
         1
@@ -120,50 +748,334 @@ fn parse_pokemon(pkmn: &[u8]) -> Option<Pokemon> {
         None
     };
 
-    // Build display text
-    let mut text = String::new();
-    match &item {
-        Some(item_name) => text.push_str(&format!("{} ({}) @ {}\n", nickname, species, item_name)),
-        None => text.push_str(&format!("{} ({})\n", nickname, species)),
-    }
-    text.push_str(&format!("Level: {}\n", level));
-    text.push_str(&format!("{} Nature\n", nature));
-    text.push_str(&format!("Ability: {}\n", ability));
-    for m in &moves {
-        text.push_str(&format!("- {}\n", m));
-    }
-    let display_text = text.trim_end().to_string();
-
     Some(Pokemon {
+        // Filled in by the caller, which knows where the struct came from
+        location: Location::Party { slot: 0 },
         nickname,
         species,
+        dex_number,
+        generation,
+        gender,
+        is_shiny,
+        markings,
+        ot_name: Some(ot_name),
+        tid: Some((ot_id & 0xFFFF) as u16),
+        sid: Some((ot_id >> 16) as u16),
         level,
+        exp,
+        exp_to_next_level,
+        met_location,
+        met_level,
+        origin_game,
+        friendship,
+        current_hp: None,
+        max_hp: None,
+        status: None,
         item,
+        mail: None,
+        ball,
         nature,
         ability,
         moves,
-        display_text,
+        evs,
+        ivs: Some(ivs),
+        hidden_power,
+        stats,
+        contest_stats: None,
+        ribbons,
+        is_traded: false,
+        is_egg,
+        is_bad_egg,
+        egg_steps_to_hatch,
+        unknown_moves,
+        parse_warnings,
+        display_text: String::new(),
     })
 }
 
-pub fn parse_sav(path: &str) -> Result<Vec<Pokemon>, String> {
-    let raw = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+/// Decode the misc substructure's ribbon word into ribbon names.
+fn decode_ribbons(word: u32) -> Vec<String> {
+    let mut ribbons = Vec::new();
+    for (i, category) in CONTEST_RIBBONS.iter().enumerate() {
+        let rank = ((word >> (i * 3)) & 0x7) as usize;
+        for suffix in CONTEST_RIBBON_RANKS.iter().take(rank) {
+            ribbons.push(format!("{} Ribbon{}", category, suffix));
+        }
+    }
+    for (i, name) in RIBBON_FLAGS.iter().enumerate() {
+        if word & (1 << (15 + i)) != 0 {
+            ribbons.push(format!("{} Ribbon", name));
+        }
+    }
+    ribbons
+}
+
+/// Battle stats at `level` from base stats, IVs, EVs and nature, using the
+/// Gen 3+ stat formula. A nature raises the stat of its row (index / 5) and
+/// lowers the stat of its column (index % 5).
+fn calc_stats(
+    base: &[u8; 6],
+    ivs: &[u8; 6],
+    evs: &[u8; 6],
+    level: u8,
+    nature: usize,
+) -> [u16; 6] {
+    let level = level as u32;
+    let mut stats = [0u16; 6];
+    for (i, stat) in stats.iter_mut().enumerate() {
+        let raw = (2 * base[i] as u32 + ivs[i] as u32 + evs[i] as u32 / 4) * level / 100;
+        *stat = if i == 0 {
+            // Shedinja's HP is always 1
+            if base[0] == 1 { 1 } else { (raw + level + 10) as u16 }
+        } else {
+            let mut value = raw + 5;
+            let (up, down) = (GAME_STAT_ORDER[nature / 5], GAME_STAT_ORDER[nature % 5]);
+            if up != down && i == up {
+                value = value * 110 / 100;
+            } else if up != down && i == down {
+                value = value * 90 / 100;
+            }
+            value as u16
+        };
+    }
+    stats
+}
+
+/// A parsed Pokemon's stats as they would be at `level`, keeping its IVs, EVs
+/// and nature. None when the species' base stats aren't known to match the
+/// game's.
+pub(crate) fn stats_at_level(mon: &Pokemon, level: u8) -> Option<[u16; 6]> {
+    let base = data::base_stats(&mon.species)?;
+    let nature = NATURES.iter().position(|&n| n == mon.nature)?;
+    Some(calc_stats(&base, mon.ivs.as_ref()?, &mon.evs, level, nature))
+}
 
-    if raw.len() < SLOT_SIZE * 2 {
-        return Err("File too small to be a valid .sav".to_string());
+/// Format EVs as "252 Atk / 4 Def / 252 Spe", skipping zero stats.
+/// Returns None when no EVs are invested.
+fn format_evs(evs: &[u8; 6]) -> Option<String> {
+    let parts: Vec<String> = evs
+        .iter()
+        .zip(STAT_NAMES)
+        .filter(|(&ev, _)| ev != 0)
+        .map(|(ev, stat)| format!("{} {}", ev, stat))
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" / "))
     }
+}
 
-    let sections = get_active_slot(&raw);
-    let sec1 = find_section(&sections, 1)?;
-    let party_count = u32_le(sec1, 0x0034) as usize;
+/// Format IVs as "31 HP / 0 Atk / ...". With `clean_paste`, 31s are left
+/// out since Showdown assumes them, and None is returned if nothing is left.
+fn format_ivs(ivs: &[u8; 6], clean_paste: bool) -> Option<String> {
+    let parts: Vec<String> = ivs
+        .iter()
+        .zip(STAT_NAMES)
+        .filter(|(&iv, _)| !(clean_paste && iv == MAX_IV))
+        .map(|(iv, stat)| format!("{} {}", iv, stat))
+        .collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" / "))
+    }
+}
+
+/// Render a Pokemon as a Showdown set.
+/// With `clean_paste`, lines Showdown already assumes by default (Level 100,
+/// Serious nature) are left out so the paste stays minimal. Happiness is only
+/// written when it isn't the max, which matters for Return and Frustration.
+/// Eggs don't get a set at all, so the species stays a surprise.
+pub(crate) fn build_display_text(mon: &Pokemon, clean_paste: bool) -> String {
+    if mon.is_bad_egg {
+        return BAD_EGG_DISPLAY_TEXT.to_string();
+    }
+    if mon.is_egg {
+        return EGG_DISPLAY_TEXT.to_string();
+    }
+    let mut text = format!("{} ({})", mon.nickname, mon.species);
+    if let Some(marker) = mon.gender.and_then(Gender::marker) {
+        text.push_str(&format!(" ({})", marker));
+    }
+    match &mon.item {
+        Some(item_name) => text.push_str(&format!(" @ {}\n", item_name)),
+        None => text.push('\n'),
+    }
+    if !(clean_paste && mon.level == 100) {
+        text.push_str(&format!("Level: {}\n", mon.level));
+    }
+    if mon.is_shiny {
+        text.push_str("Shiny: Yes\n");
+    }
+    if mon.friendship != MAX_FRIENDSHIP {
+        text.push_str(&format!("Happiness: {}\n", mon.friendship));
+    }
+    if !(clean_paste && mon.nature == "Serious") {
+        text.push_str(&format!("{} Nature\n", mon.nature));
+    }
+    text.push_str(&format!("Ability: {}\n", mon.ability));
+    if let Some(evs) = format_evs(&mon.evs) {
+        text.push_str(&format!("EVs: {}\n", evs));
+    }
+    if let Some(ivs) = mon.ivs.as_ref().and_then(|ivs| format_ivs(ivs, clean_paste)) {
+        text.push_str(&format!("IVs: {}\n", ivs));
+    }
+    for m in &mon.moves {
+        text.push_str(&format!("- {}\n", m.name));
+    }
+    text.trim_end().to_string()
+}
+
+/// What to include in a parse. The defaults are the party-only parse used
+/// while watching a save: every party field, but no boxes. Views that don't
+/// show IVs or the original trainer can leave those out as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseOptions {
+    pub include_boxes: bool,
+    pub include_ivs: bool,
+    pub include_ot: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            include_boxes: false,
+            include_ivs: true,
+            include_ot: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Clear the fields these options leave out. The export text is built
+    /// beforehand, so it still has them.
+    fn strip(&self, mon: &mut Pokemon) {
+        if !self.include_ivs {
+            mon.ivs = None;
+        }
+        if !self.include_ot {
+            mon.ot_name = None;
+            mon.tid = None;
+            mon.sid = None;
+        }
+    }
+}
+
+/// A save's party along with the game it was parsed as and any sections that
+/// failed their checksum.
+#[derive(Debug, Serialize, Clone)]
+pub struct ParseResult {
+    pub game: Game,
+    pub corrupt_sections: Vec<CorruptSection>,
+    pub party: Vec<Pokemon>,
+    /// Non-empty PC boxes, when `ParseOptions::include_boxes` is set.
+    pub boxes: Option<Vec<PcBox>>,
+}
+
+/// Parse the party of a save image already in memory, such as one received
+/// over the network or built by a test. Without `game`, it is detected.
+pub fn parse_sav_bytes(
+    raw: &[u8],
+    game: Option<Game>,
+    options: ParseOptions,
+    clean_paste: bool,
+) -> Result<ParseResult, ParseError> {
+    let sections = active_slot(raw)?;
+    let game = match game {
+        Some(game) => game,
+        None => detect_game(&sections)?,
+    };
+    let mut result = ParseResult {
+        game,
+        corrupt_sections: corrupt_sections(&sections, game.profile()),
+        party: parse_party(&sections, game.profile(), clean_paste)?,
+        boxes: match options.include_boxes {
+            true => Some(parse_pc_boxes(&sections, game.profile(), clean_paste)?),
+            false => None,
+        },
+    };
+    let boxed = result.boxes.iter_mut().flatten().flat_map(|b| &mut b.pokemon);
+    for mon in result.party.iter_mut().chain(boxed) {
+        options.strip(mon);
+    }
+    Ok(result)
+}
+
+/// Read the save at `path` and parse it with `parse_sav_bytes`.
+pub fn parse_sav(
+    path: &str,
+    io_mode: IoMode,
+    game: Option<Game>,
+    options: ParseOptions,
+    clean_paste: bool,
+) -> Result<ParseResult, ParseError> {
+    parse_sav_bytes(&reader::read_save(path, io_mode)?, game, options, clean_paste)
+}
+
+/// Parse a Pokemon and fill in the fields that depend on where it's kept, the
+/// save's trainer and the export settings.
+fn parse_owned_pokemon(
+    pkmn: &[u8],
+    layout: &PokemonLayout,
+    location: Location,
+    trainer: &Trainer,
+    clean_paste: bool,
+) -> Option<Pokemon> {
+    let mut mon = parse_pokemon(pkmn, layout)?;
+    mon.location = location;
+    mon.is_traded = !trainer.is_ot_of(pkmn);
+    mon.display_text = build_display_text(&mon, clean_paste);
+    Some(mon)
+}
+
+/// Fill in current PP from the attacks substructure (pp1-4 at +8), which only
+/// party Pokemon store.
+fn fill_move_pp(mon: &mut Pokemon, pkmn: &[u8], layout: &PokemonLayout) {
+    let slots = (0..4).filter(|slot| u16_le(pkmn, layout.attacks + slot * 2) != 0);
+    for (m, slot) in mon.moves.iter_mut().zip(slots) {
+        m.pp = Some(pkmn[layout.attacks + 8 + slot]);
+    }
+}
+
+/// Parse the party out of an already loaded save slot.
+pub(crate) fn parse_party(
+    sections: &[Section],
+    profile: &GameProfile,
+    clean_paste: bool,
+) -> Result<Vec<Pokemon>, ParseError> {
+    let trainer = Trainer::from_sections(sections, profile)?;
+    let sec = find_section(sections, profile.party_section)?;
+    let party_count = read_u32(sec, profile.party_count_offset)? as usize;
+    let size = profile.pokemon.size;
+    let mail = mail::parse_mail(sections, profile)?;
 
     let mut party = Vec::new();
     for i in 0..party_count.min(6) {
-        let off = PARTY_OFFSET + i * POKEMON_SIZE;
-        if off + POKEMON_SIZE > sec1.len() {
-            break;
-        }
-        if let Some(mon) = parse_pokemon(&sec1[off..off + POKEMON_SIZE]) {
+        let stored = bytes_at(sec, profile.party_offset + i * size, size)?;
+        let decoded = decode_pokemon(stored, profile);
+        let raw = decoded.as_slice();
+        let layout = &profile.pokemon;
+        let location = Location::Party { slot: i };
+        if let Some(mut mon) = parse_owned_pokemon(raw, layout, location, &trainer, clean_paste) {
+            mon.status = Some(Status::from_word(u32_le(raw, layout.status)));
+            mon.current_hp = Some(u16_le(raw, layout.hp));
+            mon.max_hp = Some(u16_le(raw, layout.hp + 2));
+            let mut stats = [0u16; 6];
+            stats[0] = u16_le(raw, layout.hp + 2);
+            for (i, &stat) in GAME_STAT_ORDER.iter().enumerate() {
+                stats[stat] = u16_le(raw, layout.hp + 4 + i * 2);
+            }
+            mon.stats = Some(stats);
+            // The game's own stats don't depend on the base stats table
+            mon.parse_warnings.retain(|w| *w != ParseWarning::UnverifiedBaseStats);
+            mon.contest_stats =
+                Some(ContestStats::from_bytes(&raw[layout.evs + 6..layout.evs + 12]));
+            fill_move_pp(&mut mon, raw, layout);
+            // The byte after the level is the held mail's slot, 0xFF for none
+            let mail_slot = raw[layout.level + 1] as usize;
+            mon.mail = mail.iter().find(|m| m.slot == mail_slot).cloned();
             party.push(mon);
         }
     }
@@ -171,20 +1083,195 @@ pub fn parse_sav(path: &str) -> Result<Vec<Pokemon>, String> {
     Ok(party)
 }
 
+/// Work out which game wrote the save from its game code and party. FRLG-based
+/// saves are told apart by their party: vanilla structs carry a checksum that
+/// matches once decrypted, while CFRU stores them unencrypted with it left at 0.
+/// Ruby/Sapphire saves are refused, as no profile matches their layout.
+pub(crate) fn detect_game(sections: &[Section]) -> Result<Game, ParseError> {
+    let sec0 = find_section(sections, 0)?;
+    match read_u32(sec0, GAME_CODE_OFFSET)? {
+        GAME_CODE_FRLG => {}
+        GAME_CODE_RUBY_SAPPHIRE => return Err(ParseError::UnsupportedGame("Ruby/Sapphire")),
+        _ => return Ok(Game::Emerald),
+    }
+
+    let profile = &RADICAL_RED;
+    let sec = find_section(sections, profile.party_section)?;
+    let size = profile.pokemon.size;
+    let party_count = (read_u32(sec, profile.party_count_offset)? as usize).min(6);
+    let mut encrypted = false;
+    for i in 0..party_count {
+        let mut pkmn = bytes_at(sec, profile.party_offset + i * size, size)?.to_vec();
+        encrypted |= u16_le(&pkmn, 28) != 0 && pk3::decrypt(&mut pkmn).is_ok();
+    }
+    Ok(if encrypted {
+        Game::FireRedLeafGreen
+    } else {
+        Game::RadicalRed
+    })
+}
+
+/// Each daycare slot: a box Pokemon (80 bytes, uncompressed even in CFRU), its
+/// held mail (56 bytes), then the steps walked since it was dropped off.
+const DAYCARE_POKEMON_SIZE: usize = 80;
+const DAYCARE_SLOT_SIZE: usize = 140;
+const DAYCARE_STEPS_OFFSET: usize = 136;
+const DAYCARE_SLOTS: usize = 2;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DaycareMon {
+    pub pokemon: Pokemon,
+    /// Experience gained at the daycare so far, one point per step.
+    pub exp_gained: u32,
+    /// Level once withdrawn. Equal to the current level when the species'
+    /// growth rate is unknown.
+    pub level_on_withdraw: u8,
+}
+
+/// Parse the Pokemon left at the daycare.
+pub(crate) fn parse_daycare(
+    sections: &[Section],
+    profile: &GameProfile,
+    clean_paste: bool,
+) -> Result<Vec<DaycareMon>, ParseError> {
+    let daycare = profile
+        .daycare_offset
+        .ok_or(ParseError::Unsupported { feature: "daycare" })?;
+    let trainer = Trainer::from_sections(sections, profile)?;
+    let block = profile_save_block1(sections, profile)?;
+
+    let mut mons = Vec::new();
+    for slot in 0..DAYCARE_SLOTS {
+        let off = daycare + slot * DAYCARE_SLOT_SIZE;
+        let raw = decode_pokemon(bytes_at(&block, off, DAYCARE_POKEMON_SIZE)?, profile);
+        let location = Location::Daycare { slot };
+        let Some(pokemon) =
+            parse_owned_pokemon(&raw, &profile.pokemon, location, &trainer, clean_paste)
+        else {
+            continue;
+        };
+        let exp_gained = read_u32(&block, off + DAYCARE_STEPS_OFFSET)?;
+        let level_on_withdraw = data::growth_rate(&pokemon.species)
+            .map_or(pokemon.level, |growth| {
+                growth.level_for_exp(pokemon.exp.saturating_add(exp_gained))
+            });
+        mons.push(DaycareMon {
+            pokemon,
+            exp_gained,
+            level_on_withdraw,
+        });
+    }
+    Ok(mons)
+}
+
+/// Reassemble PC storage from its sections.
+fn pc_storage(sections: &[Section], profile: &GameProfile) -> Result<Vec<u8>, ParseError> {
+    let (first, last) = profile.pc_sections;
+    let mut storage = Vec::with_capacity(PC_CHUNK * (last - first + 1) as usize);
+    for id in first..=last {
+        storage.extend_from_slice(bytes_at(find_section(sections, id)?, 0, PC_CHUNK)?);
+    }
+    Ok(storage)
+}
+
+/// Parse every boxed Pokemon, grouped by box. Empty boxes are left out.
+pub(crate) fn parse_pc_boxes(
+    sections: &[Section],
+    profile: &GameProfile,
+    clean_paste: bool,
+) -> Result<Vec<PcBox>, ParseError> {
+    let trainer = Trainer::from_sections(sections, profile)?;
+    let storage = pc_storage(sections, profile)?;
+    let size = profile.box_pokemon_size;
+
+    let mut boxes = Vec::new();
+    for box_index in 0..profile.box_count {
+        let mut pokemon = Vec::new();
+        for slot in 0..profile.box_capacity {
+            let off = 4 + (box_index * profile.box_capacity + slot) * size;
+            let raw = decode_pokemon(bytes_at(&storage, off, size)?, profile);
+            let location = Location::Box {
+                box_number: box_index + 1,
+                slot,
+            };
+            pokemon.extend(parse_owned_pokemon(
+                &raw,
+                &profile.pokemon,
+                location,
+                &trainer,
+                clean_paste,
+            ));
+        }
+        if !pokemon.is_empty() {
+            boxes.push(PcBox {
+                number: box_index + 1,
+                pokemon,
+            });
+        }
+    }
+
+    Ok(boxes)
+}
+
+/// Fixtures shared by the tests of every module that reads the sample save.
 #[cfg(test)]
-mod tests {
+pub(crate) mod test_support {
     use super::*;
 
-    const TEST_SAV: &str = concat!(
+    /// A Radical Red save with a full party of six at level 28.
+    pub const TEST_SAV: &str = concat!(
         env!("CARGO_MANIFEST_DIR"),
         "/../1636 - Pokemon Fire Red (U)(Squirrels) (patched).sav"
     );
 
+    /// The party of the sample save.
+    pub fn rr_party() -> Vec<Pokemon> {
+        parse_sav(
+            TEST_SAV,
+            IoMode::Direct,
+            Some(Game::RadicalRed),
+            ParseOptions::default(),
+            false,
+        )
+        .expect("Failed to parse .sav file")
+        .party
+    }
+
+    /// The data of section `id`, for tests that write known values into it.
+    pub fn section_mut(sections: &mut [Section], id: u16) -> &mut [u8] {
+        &mut sections.iter_mut().find(|s| s.id == id).unwrap().data
+    }
+
+    /// A CFRU party Pokemon with nothing set but a personality and `species`.
+    pub fn blank_pokemon(species: u16) -> [u8; 100] {
+        let mut pkmn = [0u8; 100];
+        pkmn[0] = 1;
+        pkmn[32..34].copy_from_slice(&species.to_le_bytes());
+        pkmn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{blank_pokemon, rr_party, TEST_SAV};
+    use super::*;
+    use crate::profile::RADICAL_RED;
+
+    /// (nickname, species, level, item, nature, moves)
+    type ExpectedMon = (
+        &'static str,
+        &'static str,
+        u8,
+        Option<&'static str>,
+        &'static str,
+        &'static [&'static str],
+    );
+
     #[test]
     fn test_parse_party_from_sav() {
-        let party = parse_sav(TEST_SAV).expect("Failed to parse .sav file");
+        let party = rr_party();
 
-        let expected: Vec<(&str, &str, u8, Option<&str>, &str, &[&str])> = vec![
+        let expected: Vec<ExpectedMon> = vec![
             ("2Kewl", "Tentacruel", 28, None, "Relaxed",
              &["Water Pulse", "Wring Out", "Supersonic", "Acid"]),
             ("Smell", "Skuntank", 28, None, "Modest",
@@ -213,7 +1300,7 @@ mod tests {
                 "Pokemon {}: item mismatch", i
             );
             assert_eq!(mon.nature, *exp_nature, "Pokemon {}: nature mismatch", i);
-            let move_strs: Vec<&str> = mon.moves.iter().map(|s| s.as_str()).collect();
+            let move_strs: Vec<&str> = mon.moves.iter().map(|m| m.name.as_str()).collect();
             assert_eq!(
                 move_strs.as_slice(),
                 *exp_moves,
@@ -222,15 +1309,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_party_locations() {
+        let party = rr_party();
+        for (i, mon) in party.iter().enumerate() {
+            assert_eq!(mon.location, Location::Party { slot: i });
+        }
+    }
+
+    #[test]
+    fn test_party_original_trainer() {
+        let party = rr_party();
+        for mon in &party {
+            assert_eq!(
+                (mon.ot_name.as_deref(), mon.tid, mon.sid),
+                (Some("Ppppppp"), Some(19242), Some(53592)),
+                "{}: OT mismatch",
+                mon.nickname
+            );
+        }
+    }
+
+    #[test]
+    fn test_party_are_own_catches() {
+        let party = rr_party();
+        assert!(party.iter().all(|m| !m.is_traded));
+    }
+
     #[test]
     fn test_display_text_format() {
-        let party = parse_sav(TEST_SAV).expect("Failed to parse .sav file");
+        let party = rr_party();
 
         let expected_first = "\
-2Kewl (Tentacruel)
+2Kewl (Tentacruel) (M)
 Level: 28
+Happiness: 118
 Relaxed Nature
 Ability: Clear Body
+IVs: 31 HP / 31 Atk / 31 Def / 31 SpA / 31 SpD / 31 Spe
 - Water Pulse
 - Wring Out
 - Supersonic
@@ -238,14 +1354,732 @@ Ability: Clear Body
         assert_eq!(party[0].display_text, expected_first, "First pokemon display_text mismatch");
 
         let expected_kaeman = "\
-Kaeman (Arbok) @ Oran Berry
+Kaeman (Arbok) (F) @ Oran Berry
 Level: 28
+Happiness: 167
 Jolly Nature
 Ability: Intimidate
+IVs: 31 HP / 31 Atk / 31 Def / 31 SpA / 31 SpD / 31 Spe
 - Thunder Fang
 - Poison Jab
 - Sucker Punch
 - Fire Fang";
         assert_eq!(party[3].display_text, expected_kaeman, "Kaeman display_text mismatch");
     }
+
+    #[test]
+    fn test_clean_paste_omits_defaults() {
+        let mon = Pokemon {
+            location: Location::Party { slot: 0 },
+            nickname: "Mew".to_string(),
+            species: "Mew".to_string(),
+            dex_number: Some(151),
+            generation: Some(1),
+            gender: Some(Gender::Genderless),
+            is_shiny: true,
+            markings: Markings::default(),
+            ot_name: Some("Red".to_string()),
+            tid: Some(12345),
+            sid: Some(54321),
+            level: 100,
+            exp: 1_059_860,
+            exp_to_next_level: None,
+            met_location: None,
+            met_level: 0,
+            origin_game: None,
+            friendship: 255,
+            current_hp: None,
+            max_hp: None,
+            status: None,
+            item: None,
+            mail: None,
+            ball: None,
+            nature: "Serious".to_string(),
+            ability: "Synchronize".to_string(),
+            moves: vec![Move {
+                name: "Psychic".to_string(),
+                pp: None,
+                max_pp: None,
+                pp_ups: 0,
+            }],
+            evs: [4, 0, 0, 252, 0, 252],
+            ivs: Some([31, 0, 31, 31, 31, 31]),
+            hidden_power: HiddenPower::from_ivs(&[31, 0, 31, 31, 31, 31]),
+            stats: None,
+            contest_stats: None,
+            ribbons: Vec::new(),
+            is_traded: false,
+            is_egg: false,
+            is_bad_egg: false,
+            egg_steps_to_hatch: None,
+            unknown_moves: Vec::new(),
+            parse_warnings: Vec::new(),
+            display_text: String::new(),
+        };
+
+        let full = build_display_text(&mon, false);
+        assert!(full.contains("Level: 100"));
+        assert!(full.contains("Serious Nature"));
+        assert!(full.contains("Shiny: Yes"));
+        assert!(!full.contains("Happiness"));
+        assert!(full.contains("EVs: 4 HP / 252 SpA / 252 Spe"));
+        assert!(full.contains("IVs: 31 HP / 0 Atk / 31 Def / 31 SpA / 31 SpD / 31 Spe"));
+
+        let clean = build_display_text(&mon, true);
+        assert_eq!(
+            clean,
+            "Mew (Mew)\nShiny: Yes\nAbility: Synchronize\n\
+             EVs: 4 HP / 252 SpA / 252 Spe\nIVs: 0 Atk\n- Psychic"
+        );
+    }
+
+    #[test]
+    fn test_unknown_moves_keep_slot() {
+        let mut pkmn = blank_pokemon(25);
+        for (i, id) in [85u16, 9999, 98].iter().enumerate() {
+            pkmn[44 + i * 2..46 + i * 2].copy_from_slice(&id.to_le_bytes());
+        }
+
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        let names: Vec<&str> = mon.moves.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["Thunderbolt", "Move #9999", "Quick Attack"]);
+        assert_eq!(mon.unknown_moves, [UnknownMove { slot: 1, id: 9999 }]);
+    }
+
+    #[test]
+    fn test_japanese_names() {
+        let mut pkmn = blank_pokemon(25);
+        // "ピカチュウ" caught by "サトシ", from a Japanese game
+        pkmn[8..18].copy_from_slice(&[0x9C, 0x56, 0x61, 0x85, 0x53, 0xFF, 0, 0, 0, 0]);
+        pkmn[18] = 1;
+        pkmn[20..27].copy_from_slice(&[0x5B, 0x64, 0x5C, 0xFF, 0, 0, 0]);
+
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(mon.nickname, "ピカチュウ");
+        assert_eq!(mon.ot_name.as_deref(), Some("サトシ"));
+    }
+
+    #[test]
+    fn test_dex_number_and_generation() {
+        let party = rr_party();
+        let dex: Vec<_> = party.iter().map(|m| (m.dex_number, m.generation)).collect();
+        assert_eq!(
+            dex,
+            [
+                (Some(73), Some(1)),
+                (Some(435), Some(4)),
+                (Some(922), Some(9)),
+                (Some(24), Some(1)),
+                (Some(404), Some(4)),
+                (Some(974), Some(9)),
+            ]
+        );
+
+        assert_eq!(data::national_dex("Charizard-Mega-X"), Some(6));
+        assert_eq!(data::national_dex("Noivern-Sevii"), Some(715));
+        assert_eq!(data::national_dex("Ho-Oh"), Some(250));
+        assert_eq!(data::national_dex("Chillet"), None);
+    }
+
+    #[test]
+    fn test_form_abilities() {
+        assert_eq!(data::ability_name("Raichu-Alola", 0), "Surge Surfer");
+        assert_eq!(data::ability_name("Unown-B", 0), "Levitate");
+        assert_eq!(data::ability_name("Cubone-Alola", 1), "Battle Armor");
+        assert_eq!(data::ability_name("Farfetch'd", 2), "Sharpness");
+        assert_eq!(data::ability_name("Missingno", 0), "???");
+    }
+
+    #[test]
+    fn test_parse_pc_boxes() {
+        let sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Failed to parse boxes");
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].number, 1);
+
+        let species: Vec<&str> = boxes[0].pokemon.iter().map(|m| m.species.as_str()).collect();
+        assert_eq!(
+            species,
+            [
+                "Cubchoo", "Gyarados", "Charcadet", "Quaxwell",
+                "Fletchinder", "Gurdurr", "Sandygast", "Linoone",
+            ]
+        );
+
+        let cubchoo = &boxes[0].pokemon[0];
+        assert_eq!(
+            cubchoo.location,
+            Location::Box {
+                box_number: 1,
+                slot: 0
+            }
+        );
+        assert_eq!(cubchoo.level, 28);
+        assert_eq!(cubchoo.item.as_deref(), Some("Aspear Berry"));
+        let moves: Vec<&str> = cubchoo.moves.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(moves, ["Icy Wind", "Aqua Jet", "Brine", "Icicle Crash"]);
+        assert!(cubchoo.moves.iter().all(|m| m.pp.is_none()));
+        assert!(!cubchoo.is_traded);
+        assert_eq!(cubchoo.current_hp, None);
+        assert_eq!(cubchoo.contest_stats, None);
+        // Gyarados (Slow) and Quaxwell (Medium Slow) need their own growth curves.
+        assert_eq!(boxes[0].pokemon[1].level, 28);
+        assert_eq!(boxes[0].pokemon[3].level, 28);
+    }
+
+    #[test]
+    fn test_gender_from_personality() {
+        let party = rr_party();
+        let genders: Vec<_> = party.iter().map(|m| m.gender).collect();
+        use Gender::*;
+        assert_eq!(
+            genders,
+            [Some(Male), Some(Female), Some(Male), Some(Female), Some(Male), Some(Female)]
+        );
+
+        assert_eq!(Gender::from_personality(0x1F, 31), Gender::Male);
+        assert_eq!(Gender::from_personality(0x1E, 31), Gender::Female);
+        assert_eq!(data::gender_ratio("Meowstic-F"), Some(data::MON_FEMALE));
+        assert_eq!(data::gender_ratio("Magearna"), Some(data::MON_GENDERLESS));
+    }
+
+    #[test]
+    fn test_shiny_from_ot_and_personality() {
+        assert!(rr_party().iter().all(|m| !m.is_shiny));
+
+        let mut pkmn = blank_pokemon(25);
+        // TID 12345, SID 54321
+        pkmn[4..8].copy_from_slice(&((54321u32 << 16) | 12345).to_le_bytes());
+
+        // PID halves that XOR with the IDs to 7: shiny
+        let shiny_pid = (0x1234u32 << 16) | (0x1234 ^ 12345 ^ 54321 ^ 7);
+        pkmn[0..4].copy_from_slice(&shiny_pid.to_le_bytes());
+        assert!(parse_pokemon(&pkmn, &RADICAL_RED.pokemon).unwrap().is_shiny);
+
+        // ... and to 8: not shiny
+        let plain_pid = (0x1234u32 << 16) | (0x1234 ^ 12345 ^ 54321 ^ 8);
+        pkmn[0..4].copy_from_slice(&plain_pid.to_le_bytes());
+        assert!(!parse_pokemon(&pkmn, &RADICAL_RED.pokemon).unwrap().is_shiny);
+    }
+
+    #[test]
+    fn test_party_hp_and_status() {
+        let party = rr_party();
+        let hp: Vec<_> = party.iter().map(|m| (m.current_hp, m.max_hp)).collect();
+        assert_eq!(hp[0], (Some(91), Some(91)));
+        assert_eq!(hp[5], (Some(107), Some(107)));
+        assert!(party.iter().all(|m| m.status == Some(Status::Healthy)));
+        let zero_contest = ContestStats::from_bytes(&[0; 6]);
+        assert!(party.iter().all(|m| m.contest_stats == Some(zero_contest)));
+
+        assert_eq!(Status::from_word(3), Status::Sleep);
+        assert_eq!(Status::from_word(1 << 6), Status::Paralysis);
+        assert_eq!(Status::from_word(1 << 7), Status::Toxic);
+    }
+
+    #[test]
+    fn test_egg_steps_to_hatch() {
+        assert!(rr_party().iter().all(|m| !m.is_egg && m.egg_steps_to_hatch.is_none()));
+
+        let mut pkmn = blank_pokemon(25);
+        pkmn[41] = 10;
+        pkmn[72..76].copy_from_slice(&(1u32 << 30).to_le_bytes());
+
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert!(mon.is_egg);
+        assert_eq!(mon.egg_steps_to_hatch, Some(2560));
+        assert_eq!(build_display_text(&mon, false), "Egg (species hidden)");
+    }
+
+    #[test]
+    fn test_move_pp_and_pp_ups() {
+        let party = rr_party();
+        let pp: Vec<_> = party[0].moves.iter().map(|m| m.pp).collect();
+        assert_eq!(pp, [Some(20), Some(5), Some(20), Some(30)]);
+        assert!(party.iter().flat_map(|m| &m.moves).all(|m| m.pp_ups == 0));
+
+        let mut pkmn = blank_pokemon(25);
+        // Slot 0 empty, 3 PP Ups on slot 1, 1 on slot 3
+        pkmn[40] = 0b0100_1100;
+        for (i, id) in [0u16, 85, 98, 129].iter().enumerate() {
+            pkmn[44 + i * 2..46 + i * 2].copy_from_slice(&id.to_le_bytes());
+        }
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        let pp_ups: Vec<_> = mon.moves.iter().map(|m| m.pp_ups).collect();
+        assert_eq!(pp_ups, [3, 0, 1]);
+    }
+
+    #[test]
+    fn test_met_location_and_origin() {
+        let party = rr_party();
+        let met: Vec<_> = party
+            .iter()
+            .map(|m| (m.met_location.as_deref(), m.met_level))
+            .collect();
+        assert_eq!(met[1], (Some("Route 4"), 15));
+        assert_eq!(met[5], (Some("Mt. Moon"), 24));
+        assert!(party.iter().all(|m| m.origin_game == Some(OriginGame::FireRed)));
+
+        assert_eq!(data::location_name(0x10), Some("Route 101"));
+        assert_eq!(data::location_name(0xFE), Some("In-game trade"));
+        assert_eq!(data::location_name(0xE0), None);
+    }
+
+    #[test]
+    fn test_ball_from_growth() {
+        let party = rr_party();
+        assert!(party.iter().all(|m| m.ball.as_deref() == Some("Poke Ball")));
+
+        let mut pkmn = blank_pokemon(25);
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(mon.ball.as_deref(), Some("Master Ball"));
+        pkmn[42] = 26;
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(mon.ball.as_deref(), Some("Dream Ball"));
+    }
+
+    #[test]
+    fn test_exp_to_next_level() {
+        let party = rr_party();
+        // Tentacruel levels on the Slow curve, Skuntank on Medium Fast.
+        assert_eq!((party[0].exp, party[0].exp_to_next_level), (27452, Some(3034)));
+        assert_eq!((party[1].exp, party[1].exp_to_next_level), (21971, Some(2418)));
+    }
+
+    #[test]
+    fn test_hidden_power() {
+        let party = rr_party();
+        let dark = HiddenPower {
+            type_name: "Dark".to_string(),
+            power: 70,
+        };
+        assert!(party.iter().all(|m| m.hidden_power == dark));
+
+        // The usual HP Fire and HP Ice spreads
+        let fire = HiddenPower::from_ivs(&[31, 30, 31, 30, 31, 30]);
+        assert_eq!((fire.type_name.as_str(), fire.power), ("Fire", 70));
+        let ice = HiddenPower::from_ivs(&[31, 30, 30, 31, 31, 31]);
+        assert_eq!((ice.type_name.as_str(), ice.power), ("Ice", 70));
+    }
+
+    #[test]
+    fn test_stats() {
+        let party = rr_party();
+        assert_eq!(party[0].stats, Some([91, 47, 63, 64, 80, 62]));
+
+        // Pawmo, Luxio and Cetoddle keep their mainline base stats in RR, so
+        // the calculator should agree with the stats the game stored.
+        for mon in [&party[2], &party[4], &party[5]] {
+            let calc = stats_at_level(mon, mon.level);
+            assert_eq!(calc, mon.stats, "{} stats mismatch", mon.species);
+        }
+
+        let sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Failed to parse boxes");
+        for mon in &boxes[0].pokemon {
+            let unverified = mon.parse_warnings.contains(&ParseWarning::UnverifiedBaseStats);
+            assert_eq!(mon.stats.is_none(), unverified, "{}", mon.species);
+        }
+
+        // Tentacruel's base stats changed in RR, so its stats can't be worked
+        // out from the mainline table: the save stores 47 Atk, not 52.
+        assert_eq!(stats_at_level(&party[0], 28), None);
+        let mut pkmn = blank_pokemon(73);
+        pkmn[RADICAL_RED.pokemon.level] = 28;
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(mon.stats, None);
+        assert_eq!(mon.parse_warnings, [ParseWarning::UnverifiedBaseStats]);
+    }
+
+    #[test]
+    fn test_ribbons() {
+        let party = rr_party();
+        assert!(party.iter().all(|m| m.ribbons.is_empty()));
+
+        // Cool rank 2, Tough rank 4, Champion and World
+        let word = 2 | (4 << 12) | (1 << 15) | (1 << 26);
+        assert_eq!(
+            decode_ribbons(word),
+            [
+                "Cool Ribbon", "Cool Ribbon Super",
+                "Tough Ribbon", "Tough Ribbon Super", "Tough Ribbon Hyper", "Tough Ribbon Master",
+                "Champion Ribbon", "World Ribbon",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markings() {
+        let mut pkmn = blank_pokemon(25);
+        pkmn[27] = 0b1001;
+
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(
+            mon.markings,
+            Markings {
+                circle: true,
+                square: false,
+                triangle: false,
+                heart: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_vanilla_frlg_party_and_boxes() {
+        use crate::profile::FIRE_RED_LEAF_GREEN;
+        use crate::savegen::{self, DEMO_PARTY};
+
+        // Boo's moves don't exist in vanilla; its slot becomes a Bad Egg below.
+        let raw = savegen::build_save(&DEMO_PARTY[4..]).expect("Save should build");
+        let mut sections = active_slot(&raw).expect("Failed to load save");
+        let layout = &FIRE_RED_LEAF_GREEN.pokemon;
+        let party_sec = &mut sections[FIRE_RED_LEAF_GREEN.party_section as usize].data;
+        let boo = FIRE_RED_LEAF_GREEN.party_offset;
+        let snorlax = boo + layout.size;
+        // Snorlax grows Slow: 156,250 exp is exactly level 50.
+        party_sec[snorlax + 36..snorlax + 40].copy_from_slice(&156_250u32.to_le_bytes());
+        let vanilla = pk3::cfru_to_vanilla(&party_sec[snorlax..snorlax + layout.size])
+            .expect("Snorlax should convert");
+        party_sec[snorlax..snorlax + layout.size].copy_from_slice(&vanilla);
+        party_sec[boo..boo + layout.size].copy_from_slice(&vanilla);
+        party_sec[boo + 28] ^= 1;
+        let (first_box, _) = FIRE_RED_LEAF_GREEN.pc_sections;
+        sections[first_box as usize].data[4..84].copy_from_slice(&vanilla[..80]);
+
+        let party = parse_party(&sections, &FIRE_RED_LEAF_GREEN, false)
+            .expect("Failed to parse vanilla party");
+        assert_eq!(party.len(), 2);
+        assert!(party[0].is_bad_egg && party[0].is_egg);
+        assert_eq!(party[0].egg_steps_to_hatch, None);
+        assert_eq!(party[0].parse_warnings[0], ParseWarning::BadEgg);
+        assert_eq!(party[0].display_text, BAD_EGG_DISPLAY_TEXT);
+        assert!(!party[1].is_bad_egg);
+        assert!(party[1].parse_warnings.is_empty());
+        assert_eq!(party[1].species, "Snorlax");
+        assert_eq!(party[1].level, 50);
+        assert_eq!(party[1].item.as_deref(), Some("Leftovers"));
+        let moves: Vec<&str> = party[1].moves.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(moves, ["Body Slam", "Rest", "Crunch", "Earthquake"]);
+
+        let boxes = parse_pc_boxes(&sections, &FIRE_RED_LEAF_GREEN, false)
+            .expect("Failed to parse vanilla boxes");
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(boxes[0].pokemon[0].species, "Snorlax");
+        assert_eq!(boxes[0].pokemon[0].level, 50);
+
+        // The same bytes read as CFRU are scrambled.
+        let cfru = parse_party(&sections, &RADICAL_RED, false).expect("Failed to parse party");
+        assert_ne!(cfru.first().map(|m| m.species.as_str()), Some("Snorlax"));
+    }
+
+    #[test]
+    fn test_emerald_party_offset() {
+        use crate::profile::EMERALD;
+        use crate::savegen::{self, DEMO_PARTY};
+
+        let raw = savegen::build_save(&DEMO_PARTY[5..]).expect("Save should build");
+        let mut sections = active_slot(&raw).expect("Failed to load save");
+        let size = EMERALD.pokemon.size;
+        let sec = &mut sections[EMERALD.party_section as usize].data;
+        let frlg = RADICAL_RED.party_offset;
+        let vanilla =
+            pk3::cfru_to_vanilla(&sec[frlg..frlg + size]).expect("Snorlax should convert");
+        sec[frlg..frlg + size].fill(0);
+        sec[EMERALD.party_offset..EMERALD.party_offset + size].copy_from_slice(&vanilla);
+        sec[EMERALD.party_count_offset] = 1;
+
+        let party = parse_party(&sections, &EMERALD, false).expect("Failed to parse party");
+        assert_eq!(party.len(), 1);
+        assert_eq!(party[0].species, "Snorlax");
+        assert_eq!(party[0].nickname, "Snorlax");
+    }
+
+    #[test]
+    fn test_detect_game() {
+        use crate::savegen::{self, DEMO_PARTY};
+
+        let sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        assert_eq!(detect_game(&sections), Ok(Game::RadicalRed));
+
+        let raw = savegen::build_save(&DEMO_PARTY[5..]).expect("Save should build");
+        let mut sections = active_slot(&raw).expect("Failed to load save");
+        let size = RADICAL_RED.pokemon.size;
+        let off = RADICAL_RED.party_offset;
+        let sec = &mut sections[RADICAL_RED.party_section as usize].data;
+        let vanilla =
+            pk3::cfru_to_vanilla(&sec[off..off + size]).expect("Snorlax should convert");
+        sec[off..off + size].copy_from_slice(&vanilla);
+        assert_eq!(detect_game(&sections), Ok(Game::FireRedLeafGreen));
+
+        sections[0].data[GAME_CODE_OFFSET..GAME_CODE_OFFSET + 4]
+            .copy_from_slice(&0x5EC0_4E7Au32.to_le_bytes());
+        assert_eq!(detect_game(&sections), Ok(Game::Emerald));
+
+        sections[0].data[GAME_CODE_OFFSET..GAME_CODE_OFFSET + 4].fill(0);
+        assert_eq!(
+            detect_game(&sections),
+            Err(ParseError::UnsupportedGame("Ruby/Sapphire"))
+        );
+    }
+
+    #[test]
+    fn test_section_checksums() {
+        let mut sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        assert_eq!(corrupt_sections(&sections, &RADICAL_RED), []);
+
+        let party = sections.iter_mut().find(|s| s.id == 1).expect("Section 1 should exist");
+        party.data[RADICAL_RED.party_offset] ^= 1;
+        let corrupt = corrupt_sections(&sections, &RADICAL_RED);
+        assert_eq!(corrupt.len(), 1);
+        assert_eq!(corrupt[0].id, 1);
+        assert_ne!(corrupt[0].actual, corrupt[0].expected);
+    }
+
+    #[test]
+    fn test_fresh_save_slots() {
+        use crate::savegen;
+
+        // A new game writes slot A and leaves slot B erased.
+        let mut raw = savegen::demo_save().unwrap();
+        raw[SLOT_SIZE..].fill(0xFF);
+        let sections = active_slot(&raw).expect("Slot A should be used");
+        let party = parse_party(&sections, &RADICAL_RED, false).expect("Failed to parse party");
+        assert_eq!(party.len(), 6);
+
+        raw[..SLOT_SIZE].fill(0xFF);
+        assert_eq!(active_slot(&raw).err(), Some(ParseError::NoCompletedSave));
+    }
+
+    #[test]
+    fn test_64k_and_padded_saves() {
+        use crate::savegen;
+
+        let demo = savegen::demo_save().unwrap();
+        let mut small = demo[..0x10000].to_vec();
+        let sections = active_slot(&small).expect("64KiB save should load");
+        assert_eq!(parse_party(&sections, &RADICAL_RED, false).unwrap().len(), 6);
+
+        small.truncate(SLOT_SIZE - 1);
+        assert!(active_slot(&small).is_err());
+
+        let mut footer = demo.clone();
+        footer.extend_from_slice(&[0xAB; 0x4010]);
+        let sections = active_slot(&footer).expect("Save with a footer should load");
+        assert_eq!(parse_party(&sections, &RADICAL_RED, false).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_malformed_saves_never_panic() {
+        use crate::savegen;
+
+        let parse_all = |raw: &[u8]| {
+            for game in [Game::RadicalRed, Game::FireRedLeafGreen, Game::Emerald] {
+                let options = ParseOptions {
+                    include_boxes: true,
+                    ..ParseOptions::default()
+                };
+                let _ = parse_sav_bytes(raw, Some(game), options, false);
+                if let Ok(sections) = active_slot(raw) {
+                    let _ = parse_daycare(&sections, game.profile(), false);
+                }
+            }
+            parse_sav_bytes(raw, None, ParseOptions::default(), false)
+        };
+
+        let demo = savegen::demo_save().unwrap();
+        for len in [0, 1, SECTION_SIZE, SLOT_SIZE - 1] {
+            assert_eq!(parse_all(&demo[..len]).err(), Some(ParseError::TooSmall { len }));
+        }
+        for len in [SLOT_SIZE + 7, SLOT_SIZE * 2 - 1] {
+            assert!(parse_all(&demo[..len]).is_ok());
+        }
+
+        // xorshift, so the garbage is the same on every run
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+        let garbage: Vec<u8> = (0..demo.len()).map(|_| next() as u8).collect();
+        assert_eq!(parse_all(&garbage).err(), Some(ParseError::NoCompletedSave));
+        for _ in 0..20 {
+            let mut scribbled = demo.clone();
+            for _ in 0..500 {
+                let i = next() % scribbled.len();
+                scribbled[i] = next() as u8;
+            }
+            let _ = parse_all(&scribbled);
+        }
+
+        assert_eq!(
+            read_u16(&[1], 0),
+            Err(ParseError::OutOfBounds {
+                offset: 0,
+                len: 2,
+                size: 1
+            })
+        );
+        assert!(bytes_at(&[], usize::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn test_parse_daycare() {
+        use crate::profile::FIRE_RED_LEAF_GREEN;
+        use crate::savegen::{self, DEMO_PARTY};
+
+        let raw = savegen::build_save(&DEMO_PARTY[5..]).expect("Save should build");
+        let mut sections = active_slot(&raw).expect("Failed to load save");
+        let size = RADICAL_RED.pokemon.size;
+        let off = RADICAL_RED.party_offset;
+        let party_sec = &mut sections[RADICAL_RED.party_section as usize].data;
+        // Snorlax grows Slow: 156,250 exp is level 50 and 165,813 is 51.
+        party_sec[off + 36..off + 40].copy_from_slice(&156_250u32.to_le_bytes());
+        let vanilla =
+            pk3::cfru_to_vanilla(&party_sec[off..off + size]).expect("Snorlax should convert");
+        // SaveBlock1 0x2F80 is 0x100 into section 4.
+        let daycare = &mut sections[4].data[0x100..];
+        daycare[..80].copy_from_slice(&vanilla[..80]);
+        daycare[136..140].copy_from_slice(&10_000u32.to_le_bytes());
+
+        let mons = parse_daycare(&sections, &FIRE_RED_LEAF_GREEN, false)
+            .expect("Failed to parse daycare");
+        assert_eq!(mons.len(), 1);
+        assert_eq!(mons[0].pokemon.species, "Snorlax");
+        assert_eq!(mons[0].pokemon.location, Location::Daycare { slot: 0 });
+        assert_eq!(mons[0].pokemon.level, 50);
+        assert_eq!(mons[0].exp_gained, 10_000);
+        assert_eq!(mons[0].level_on_withdraw, 51);
+    }
+
+    #[test]
+    fn test_parse_cfru_daycare() {
+        let mut sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        assert!(parse_daycare(&sections, &RADICAL_RED, false).unwrap().is_empty());
+        // The egg step counter, after both slots and the u16 offspring
+        // personality, ticks on every step whether or not the daycare is in
+        // use. It's the only byte set there, which places the daycare.
+        let block = profile_save_block1(&sections, &RADICAL_RED).unwrap();
+        let daycare = &block[0x2F80..0x2F80 + 2 * DAYCARE_SLOT_SIZE + 3];
+        assert_eq!(daycare[2 * DAYCARE_SLOT_SIZE + 2], 0xF1);
+        assert_eq!(daycare.iter().filter(|&&b| b != 0).count(), 1);
+
+        // Leave Tentacruel at the daycare. SaveBlock1 0x2F80 is 0xFA0 into
+        // CFRU's 0xFF0-byte section 3, and its steps land 0x38 into section 4.
+        let (off, size) = (RADICAL_RED.party_offset, RADICAL_RED.pokemon.size);
+        let mut tentacruel = find_section(&sections, 1).unwrap()[off..off + size].to_vec();
+        tentacruel[RADICAL_RED.pokemon.level] = 0;
+        let sec3 = sections.iter_mut().find(|s| s.id == 3).unwrap();
+        sec3.data[0xFA0..0xFF0].copy_from_slice(&tentacruel[..DAYCARE_POKEMON_SIZE]);
+        let sec4 = sections.iter_mut().find(|s| s.id == 4).unwrap();
+        sec4.data[0x38..0x3C].copy_from_slice(&500u32.to_le_bytes());
+
+        let mons = parse_daycare(&sections, &RADICAL_RED, false).expect("Failed to parse daycare");
+        assert_eq!(mons.len(), 1);
+        assert_eq!(mons[0].pokemon.nickname, "2Kewl");
+        assert_eq!(mons[0].pokemon.location, Location::Daycare { slot: 0 });
+        assert_eq!(mons[0].pokemon.level, 28);
+        assert_eq!(mons[0].exp_gained, 500);
+        assert!(!mons[0].pokemon.is_traded);
+    }
+
+    #[test]
+    fn test_parse_sav_bytes() {
+        let demo = crate::savegen::demo_save().unwrap();
+        let result = parse_sav_bytes(&demo, None, ParseOptions::default(), false)
+            .expect("Demo save should parse");
+        assert_eq!(result.game, Game::RadicalRed);
+        assert!(result.corrupt_sections.is_empty());
+        assert_eq!(result.party.len(), 6);
+        assert!(result.boxes.is_none());
+        assert!(parse_sav_bytes(&[0u8; 16], None, ParseOptions::default(), false).is_err());
+
+        let options = ParseOptions {
+            include_boxes: true,
+            ..ParseOptions::default()
+        };
+        let full = parse_sav(TEST_SAV, IoMode::Direct, None, options, false)
+            .expect("Failed to parse .sav file");
+        assert!(!full.boxes.expect("Boxes were requested").is_empty());
+    }
+
+    #[test]
+    fn test_parse_without_ivs_or_ot() {
+        let options = ParseOptions {
+            include_boxes: true,
+            include_ivs: false,
+            include_ot: false,
+        };
+        let full = parse_sav(TEST_SAV, IoMode::Direct, None, ParseOptions::default(), false)
+            .expect("Failed to parse .sav file");
+        let trimmed = parse_sav(TEST_SAV, IoMode::Direct, None, options, false)
+            .expect("Failed to parse .sav file");
+
+        let boxed = trimmed.boxes.as_ref().expect("Boxes were requested");
+        for mon in trimmed.party.iter().chain(boxed.iter().flat_map(|b| &b.pokemon)) {
+            assert_eq!(mon.ivs, None);
+            assert_eq!((mon.ot_name.as_deref(), mon.tid, mon.sid), (None, None, None));
+        }
+        // The export text is built before the fields are cleared
+        for (mon, trimmed) in full.party.iter().zip(&trimmed.party) {
+            assert!(mon.ivs.is_some() && mon.ot_name.is_some());
+            assert_eq!(mon.display_text, trimmed.display_text);
+        }
+    }
+
+    #[test]
+    fn test_party_mail() {
+        let mut sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        // SaveBlock1 0x2CD0 is 0xCF0 into CFRU's 0xFF0-byte section 3. The game
+        // clears unused mail to Bulbasaur with no item, so every slot should
+        // match that if the mail array really starts there.
+        let sec3 = &mut sections.iter_mut().find(|s| s.id == 3).unwrap().data;
+        for slot in 0..16 {
+            let mail = &sec3[0xCF0 + slot * 36..0xCF0 + (slot + 1) * 36];
+            assert_eq!((u16_le(mail, 30), u16_le(mail, 32)), (1, 0), "mail slot {}", slot);
+        }
+        // Give mail slot 0 an author and item: words, author, TID, species, item.
+        let mail = &mut sec3[0xCF0..0xCF0 + 36];
+        mail[18..22].copy_from_slice(&[0xCC, 0xD9, 0xD8, 0xFF]);
+        mail[32..34].copy_from_slice(&121u16.to_le_bytes());
+        // The first party member holds mail slot 0
+        let level = RADICAL_RED.party_offset + RADICAL_RED.pokemon.level;
+        let sec1 = &mut sections.iter_mut().find(|s| s.id == 1).unwrap().data;
+        sec1[level + 1] = 0;
+
+        let party = parse_party(&sections, &RADICAL_RED, false).expect("Failed to parse party");
+        let held = party[0].mail.as_ref().expect("First Pokemon should hold mail");
+        assert_eq!((held.slot, held.author.as_str()), (0, "Red"));
+        assert_eq!(held.item, "Orange Mail");
+        assert!(party[1].mail.is_none());
+    }
+
+    #[test]
+    fn test_save_charset() {
+        let mut sections = load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        assert_eq!(save_charset(&sections, &RADICAL_RED).unwrap(), Charset::International);
+
+        let off = RADICAL_RED.party_offset;
+        let sec = sections.iter_mut().find(|s| s.id == 1).unwrap();
+        sec.data[off + 18] = 1;
+        assert_eq!(save_charset(&sections, &RADICAL_RED).unwrap(), Charset::Japanese);
+
+        // Eggs are always marked Japanese, so the next Pokemon decides
+        let sec = sections.iter_mut().find(|s| s.id == 1).unwrap();
+        sec.data[off + 75] |= 0x40;
+        assert_eq!(save_charset(&sections, &RADICAL_RED).unwrap(), Charset::International);
+    }
+
+    #[test]
+    fn test_unknown_species_warning() {
+        assert!(rr_party().iter().all(|m| m.parse_warnings.is_empty()));
+
+        let pkmn = blank_pokemon(0xFFFF);
+        let mon = parse_pokemon(&pkmn, &RADICAL_RED.pokemon).expect("Pokemon should be present");
+        assert_eq!(mon.species, "???");
+        assert!(!mon.is_bad_egg);
+        assert_eq!(mon.parse_warnings, [ParseWarning::UnknownSpecies { id: 0xFFFF }]);
+    }
 }
@@ -0,0 +1,172 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::reader;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CommandStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    pub bytes_read: u64,
+    /// Calls answered from an earlier parse instead of parsing again.
+    pub cache_hits: u64,
+}
+
+/// What one call cost, returned alongside its result.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ResponseMeta {
+    pub duration_ms: f64,
+    pub bytes_read: u64,
+    pub cache_hit: bool,
+}
+
+/// A command's result with its `meta` block. The result's own fields stay at
+/// the top level, so callers that don't look at `meta` are unaffected.
+#[derive(Debug, Serialize, Clone)]
+pub struct WithMeta<T> {
+    #[serde(flatten)]
+    pub result: T,
+    pub meta: ResponseMeta,
+}
+
+/// Timing totals per command, kept in Tauri managed state for the app's lifetime.
+#[derive(Default)]
+pub struct PerfStats(Mutex<HashMap<String, CommandStats>>);
+
+impl PerfStats {
+    /// Run a command body, recording how long it took and how many bytes it
+    /// read from disk, retries included.
+    pub fn time<T, E>(&self, command: &str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        reader::take_bytes_read();
+        let started = Instant::now();
+        let result = f();
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        self.record(
+            command,
+            elapsed_ms,
+            reader::take_bytes_read(),
+            result.is_ok(),
+        );
+        result
+    }
+
+    /// Like `time`, for a body that reports whether it was a cache hit, and
+    /// returning this call's cost with the result.
+    pub fn time_with_meta<T, E>(
+        &self,
+        command: &str,
+        f: impl FnOnce() -> Result<(T, bool), E>,
+    ) -> Result<WithMeta<T>, E> {
+        reader::take_bytes_read();
+        let started = Instant::now();
+        let result = f();
+        let meta = ResponseMeta {
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+            bytes_read: reader::take_bytes_read(),
+            cache_hit: matches!(result, Ok((_, true))),
+        };
+        self.record(command, meta.duration_ms, meta.bytes_read, result.is_ok());
+        if meta.cache_hit {
+            self.count_cache_hit(command);
+        }
+        let (result, _) = result?;
+        Ok(WithMeta { result, meta })
+    }
+
+    fn count_cache_hit(&self, command: &str) {
+        let mut stats = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        stats.entry(command.to_string()).or_default().cache_hits += 1;
+    }
+
+    fn record(&self, command: &str, elapsed_ms: f64, bytes: u64, ok: bool) {
+        let mut stats = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.entry(command.to_string()).or_default();
+        entry.calls += 1;
+        if !ok {
+            entry.errors += 1;
+        }
+        entry.total_ms += elapsed_ms;
+        entry.max_ms = entry.max_ms.max(elapsed_ms);
+        entry.last_ms = elapsed_ms;
+        entry.bytes_read += bytes;
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, CommandStats> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+
+    #[test]
+    fn test_record_aggregates_per_command() {
+        let perf = PerfStats::default();
+        perf.record("parse_sav_file", 4.0, 100, true);
+        perf.record("parse_sav_file", 10.0, 100, false);
+        perf.record("parse_mail", 1.0, 50, true);
+
+        let stats = perf.snapshot();
+        let parse = &stats["parse_sav_file"];
+        assert_eq!(parse.calls, 2);
+        assert_eq!(parse.errors, 1);
+        assert_eq!(parse.total_ms, 14.0);
+        assert_eq!(parse.max_ms, 10.0);
+        assert_eq!(parse.last_ms, 10.0);
+        assert_eq!(parse.bytes_read, 200);
+        assert_eq!(stats["parse_mail"].calls, 1);
+    }
+
+    #[test]
+    fn test_time_counts_bytes_read() {
+        let perf = PerfStats::default();
+        let raw = perf
+            .time("read", || {
+                reader::read_save(TEST_SAV, reader::IoMode::Direct)
+            })
+            .expect("Failed to read save");
+        let _ = perf.time("missing", || {
+            reader::read_save("/nonexistent.sav", reader::IoMode::Direct)
+        });
+
+        let stats = perf.snapshot();
+        assert_eq!(stats["read"].bytes_read, raw.len() as u64);
+        assert_eq!(
+            (stats["missing"].bytes_read, stats["missing"].errors),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn test_time_with_meta() {
+        let perf = PerfStats::default();
+        let read = |cache_hit| {
+            perf.time_with_meta("parse", || {
+                reader::read_save(TEST_SAV, reader::IoMode::Direct).map(|raw| (raw, cache_hit))
+            })
+        };
+        let first = read(false).expect("Failed to read save");
+        let second = read(true).expect("Failed to read save");
+        assert_eq!(first.meta.bytes_read, first.result.len() as u64);
+        assert!(!first.meta.cache_hit && second.meta.cache_hit);
+
+        let stats = perf.snapshot();
+        assert_eq!((stats["parse"].calls, stats["parse"].cache_hits), (2, 1));
+        assert_eq!(stats["parse"].last_ms, second.meta.duration_ms);
+
+        let json = serde_json::to_value(WithMeta {
+            result: serde_json::json!({ "party": [] }),
+            meta: second.meta,
+        })
+        .unwrap();
+        assert_eq!(json["party"], serde_json::json!([]));
+        assert_eq!(json["meta"]["cache_hit"], true);
+    }
+}
@@ -0,0 +1,307 @@
+//! Helpers for the vanilla Gen 3 Pokemon struct (.pk3), for tools that need to
+//! move Pokemon between vanilla saves and CFRU's fixed, unencrypted layout.
+//!
+//! A vanilla struct has a 32-byte header (PID, OT ID, nickname, ..., checksum
+//! at 28) followed by four 12-byte substructures that are shuffled by
+//! PID % 24 and XORed with PID ^ OT ID.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+use crate::parser::{u16_le, u32_le};
+
+/// Offset and size of the four substructures.
+pub const SUBSTRUCT_OFFSET: usize = 32;
+pub const SUBSTRUCT_SIZE: usize = 12;
+const SUBSTRUCT_COUNT: usize = 4;
+const CHECKSUM_OFFSET: usize = 28;
+
+/// Substructure kinds, in the order CFRU stores them.
+pub const GROWTH: usize = 0;
+pub const ATTACKS: usize = 1;
+pub const EVS: usize = 2;
+pub const MISC: usize = 3;
+
+/// Party struct size in both layouts.
+pub const PARTY_SIZE: usize = 100;
+
+/// Highest species, move and item IDs the vanilla games define. Anything above
+/// only exists in CFRU and can't be converted down.
+const VANILLA_MAX_SPECIES: u16 = 411;
+const VANILLA_MAX_MOVE: u16 = 354;
+const VANILLA_MAX_ITEM: u16 = 376;
+/// Premier Ball; CFRU adds its new balls after it.
+const VANILLA_MAX_BALL: u16 = 12;
+
+/// Fixed-order offsets used by the conversion.
+const SPECIES_OFFSET: usize = SUBSTRUCT_OFFSET;
+const ITEM_OFFSET: usize = SUBSTRUCT_OFFSET + 2;
+/// CFRU keeps the ball in the Growth substructure's spare byte, 0-indexed
+/// (0 = Master Ball); vanilla keeps it in origins bits 11-14, 1-indexed.
+const CFRU_BALL_OFFSET: usize = SUBSTRUCT_OFFSET + 10;
+const MOVES_OFFSET: usize = SUBSTRUCT_OFFSET + ATTACKS * SUBSTRUCT_SIZE;
+const ORIGINS_OFFSET: usize = SUBSTRUCT_OFFSET + MISC * SUBSTRUCT_SIZE + 2;
+const IV_WORD_OFFSET: usize = SUBSTRUCT_OFFSET + MISC * SUBSTRUCT_SIZE + 4;
+const POKE_BALL: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    Vanilla,
+    Cfru,
+}
+
+/// Which substructure sits at each position, indexed by PID % 24.
+#[rustfmt::skip]
+const SUBSTRUCT_ORDERS: [[usize; 4]; 24] = [
+    [0, 1, 2, 3], [0, 1, 3, 2], [0, 2, 1, 3], [0, 2, 3, 1], [0, 3, 1, 2], [0, 3, 2, 1],
+    [1, 0, 2, 3], [1, 0, 3, 2], [1, 2, 0, 3], [1, 2, 3, 0], [1, 3, 0, 2], [1, 3, 2, 0],
+    [2, 0, 1, 3], [2, 0, 3, 1], [2, 1, 0, 3], [2, 1, 3, 0], [2, 3, 0, 1], [2, 3, 1, 0],
+    [3, 0, 1, 2], [3, 0, 2, 1], [3, 1, 0, 2], [3, 1, 2, 0], [3, 2, 0, 1], [3, 2, 1, 0],
+];
+
+/// The substructure kind (GROWTH, ATTACKS, EVS, MISC) at each of the four
+/// positions for a given PID.
+pub fn substructure_order(personality: u32) -> [usize; 4] {
+    SUBSTRUCT_ORDERS[(personality % 24) as usize]
+}
+
+/// Checksum of the 48 substructure bytes: the sum of their u16 words.
+/// Must be computed on decrypted data.
+pub fn checksum(substructs: &[u8]) -> u16 {
+    (0..substructs.len() / 2).fold(0u16, |sum, i| sum.wrapping_add(u16_le(substructs, i * 2)))
+}
+
+/// XOR the substructures with PID ^ OT ID. Applying it twice is a no-op.
+fn crypt(pkmn: &mut [u8]) {
+    let key = u32_le(pkmn, 0) ^ u32_le(pkmn, 4);
+    let data = &mut pkmn[SUBSTRUCT_OFFSET..SUBSTRUCT_OFFSET + SUBSTRUCT_SIZE * SUBSTRUCT_COUNT];
+    for word in data.chunks_exact_mut(4) {
+        let value = u32::from_le_bytes([word[0], word[1], word[2], word[3]]) ^ key;
+        word.copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Reorder the substructures. With `to_fixed`, go from PID order to
+/// Growth/Attacks/EVs/Misc order; otherwise the reverse.
+fn reorder(pkmn: &mut [u8], to_fixed: bool) {
+    let order = substructure_order(u32_le(pkmn, 0));
+    let start = SUBSTRUCT_OFFSET;
+    let original = pkmn[start..start + SUBSTRUCT_SIZE * SUBSTRUCT_COUNT].to_vec();
+    for (pos, &kind) in order.iter().enumerate() {
+        let (from, to) = if to_fixed { (pos, kind) } else { (kind, pos) };
+        let dst = start + to * SUBSTRUCT_SIZE;
+        pkmn[dst..dst + SUBSTRUCT_SIZE]
+            .copy_from_slice(&original[from * SUBSTRUCT_SIZE..(from + 1) * SUBSTRUCT_SIZE]);
+    }
+}
+
+/// End of the substructures, checking `pkmn` is long enough to hold them.
+fn substructs_end(pkmn: &[u8]) -> Result<usize, ParseError> {
+    let end = SUBSTRUCT_OFFSET + SUBSTRUCT_SIZE * SUBSTRUCT_COUNT;
+    if pkmn.len() < end {
+        return Err(ParseError::BadLength {
+            expected: end,
+            actual: pkmn.len(),
+        });
+    }
+    Ok(end)
+}
+
+/// Decrypt a vanilla struct in place, leaving its substructures in the fixed
+/// Growth/Attacks/EVs/Misc order CFRU uses. Fails if the checksum doesn't match.
+pub fn decrypt(pkmn: &mut [u8]) -> Result<(), ParseError> {
+    let end = substructs_end(pkmn)?;
+    crypt(pkmn);
+    reorder(pkmn, true);
+    let expected = u16_le(pkmn, CHECKSUM_OFFSET);
+    let actual = checksum(&pkmn[SUBSTRUCT_OFFSET..end]);
+    if actual != expected {
+        return Err(ParseError::ChecksumMismatch { expected, actual });
+    }
+    Ok(())
+}
+
+/// Encrypt a struct whose substructures are in the fixed order: write the
+/// checksum, shuffle by PID and XOR with PID ^ OT ID. Fails if the struct is
+/// too short to hold the substructures.
+pub fn encrypt(pkmn: &mut [u8]) -> Result<(), ParseError> {
+    let end = substructs_end(pkmn)?;
+    let sum = checksum(&pkmn[SUBSTRUCT_OFFSET..end]);
+    pkmn[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 2].copy_from_slice(&sum.to_le_bytes());
+    reorder(pkmn, false);
+    crypt(pkmn);
+    Ok(())
+}
+
+/// Convert a vanilla party struct to CFRU's layout. In CFRU, PID parity picks
+/// the regular ability and bit 31 of the IV word means hidden ability, so the
+/// vanilla ability bit is dropped.
+pub fn vanilla_to_cfru(pkmn: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut out = party_struct(pkmn)?;
+    decrypt(&mut out)?;
+    out[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 2].fill(0);
+
+    let origins = u16_le(&out, ORIGINS_OFFSET);
+    let ball = match (origins >> 11) & 0xF {
+        0 => POKE_BALL,
+        ball => ball,
+    };
+    out[CFRU_BALL_OFFSET] = (ball - 1) as u8;
+    out[ORIGINS_OFFSET..ORIGINS_OFFSET + 2].copy_from_slice(&(origins & !0x7800).to_le_bytes());
+
+    let iv_word = u32_le(&out, IV_WORD_OFFSET) & !(1 << 31);
+    out[IV_WORD_OFFSET..IV_WORD_OFFSET + 4].copy_from_slice(&iv_word.to_le_bytes());
+    Ok(out)
+}
+
+/// Convert a CFRU party struct to the vanilla layout. Fails when the species,
+/// item, a move or the ball doesn't exist in the vanilla games. A hidden ability can't
+/// be represented and falls back to the PID's regular ability.
+pub fn cfru_to_vanilla(pkmn: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut out = party_struct(pkmn)?;
+
+    let species = u16_le(&out, SPECIES_OFFSET);
+    if species > VANILLA_MAX_SPECIES {
+        return Err(ParseError::NotInVanilla {
+            kind: "Species",
+            id: species,
+        });
+    }
+    let item = u16_le(&out, ITEM_OFFSET);
+    if item > VANILLA_MAX_ITEM {
+        return Err(ParseError::NotInVanilla {
+            kind: "Item",
+            id: item,
+        });
+    }
+    for slot in 0..4 {
+        let id = u16_le(&out, MOVES_OFFSET + slot * 2);
+        if id > VANILLA_MAX_MOVE {
+            return Err(ParseError::NotInVanilla { kind: "Move", id });
+        }
+    }
+
+    let ball = out[CFRU_BALL_OFFSET] as u16 + 1;
+    if ball > VANILLA_MAX_BALL {
+        return Err(ParseError::NotInVanilla {
+            kind: "Ball",
+            id: ball,
+        });
+    }
+    out[CFRU_BALL_OFFSET] = 0;
+    let origins = (u16_le(&out, ORIGINS_OFFSET) & !0x7800) | (ball << 11);
+    out[ORIGINS_OFFSET..ORIGINS_OFFSET + 2].copy_from_slice(&origins.to_le_bytes());
+
+    let personality = u32_le(&out, 0);
+    let iv_word = (u32_le(&out, IV_WORD_OFFSET) & !(1 << 31)) | ((personality & 1) << 31);
+    out[IV_WORD_OFFSET..IV_WORD_OFFSET + 4].copy_from_slice(&iv_word.to_le_bytes());
+
+    encrypt(&mut out)?;
+    Ok(out)
+}
+
+/// Convert a party struct to `to`, assuming it is currently in the other format.
+pub fn convert(pkmn: &[u8], to: Format) -> Result<Vec<u8>, ParseError> {
+    match to {
+        Format::Cfru => vanilla_to_cfru(pkmn),
+        Format::Vanilla => cfru_to_vanilla(pkmn),
+    }
+}
+
+fn party_struct(pkmn: &[u8]) -> Result<Vec<u8>, ParseError> {
+    if pkmn.len() != PARTY_SIZE {
+        return Err(ParseError::BadLength {
+            expected: PARTY_SIZE,
+            actual: pkmn.len(),
+        });
+    }
+    Ok(pkmn.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let mut pkmn = [0u8; 100];
+        // PID % 24 = 9: Attacks, EVs, Misc, Growth
+        pkmn[0..4].copy_from_slice(&0x1234_5681u32.to_le_bytes());
+        pkmn[4..8].copy_from_slice(&0xD158_4B2Au32.to_le_bytes());
+        for (i, b) in pkmn[32..80].iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let plain = pkmn;
+
+        encrypt(&mut pkmn).expect("Struct is long enough");
+        assert_ne!(pkmn[32..80], plain[32..80]);
+        assert_eq!(
+            substructure_order(0x1234_5681),
+            [ATTACKS, EVS, MISC, GROWTH]
+        );
+
+        decrypt(&mut pkmn).expect("Checksum should match");
+        assert_eq!(pkmn[32..80], plain[32..80]);
+
+        encrypt(&mut pkmn).expect("Struct is long enough");
+        pkmn[40] ^= 1;
+        assert!(matches!(
+            decrypt(&mut pkmn),
+            Err(ParseError::ChecksumMismatch { .. })
+        ));
+        assert_eq!(
+            decrypt(&mut pkmn[..40]),
+            Err(ParseError::BadLength {
+                expected: 80,
+                actual: 40
+            })
+        );
+    }
+
+    #[test]
+    fn test_encrypt_short_buffer() {
+        let mut pkmn = [0u8; 79];
+        assert_eq!(
+            encrypt(&mut pkmn),
+            Err(ParseError::BadLength {
+                expected: 80,
+                actual: 79
+            })
+        );
+        assert_eq!(pkmn, [0u8; 79]);
+    }
+
+    #[test]
+    fn test_convert_between_layouts() {
+        let mut cfru = [0u8; PARTY_SIZE];
+        cfru[0..4].copy_from_slice(&0x1234_5681u32.to_le_bytes());
+        cfru[4..8].copy_from_slice(&0xD158_4B2Au32.to_le_bytes());
+        cfru[SPECIES_OFFSET..SPECIES_OFFSET + 2].copy_from_slice(&25u16.to_le_bytes());
+        cfru[MOVES_OFFSET..MOVES_OFFSET + 2].copy_from_slice(&85u16.to_le_bytes());
+        cfru[CFRU_BALL_OFFSET] = 1;
+        cfru[84] = 50;
+
+        let vanilla = convert(&cfru, Format::Vanilla).expect("Pikachu should convert");
+        let mut decrypted = vanilla.clone();
+        decrypt(&mut decrypted).expect("Checksum should match");
+        assert_eq!((u16_le(&decrypted, ORIGINS_OFFSET) >> 11) & 0xF, 2);
+        assert_eq!(decrypted[84], 50);
+
+        let back = convert(&vanilla, Format::Cfru).expect("Round trip should convert");
+        assert_eq!(back[SPECIES_OFFSET..], cfru[SPECIES_OFFSET..]);
+
+        let mut new_ball = cfru;
+        new_ball[CFRU_BALL_OFFSET] = 24;
+        assert_eq!(
+            convert(&new_ball, Format::Vanilla),
+            Err(ParseError::NotInVanilla {
+                kind: "Ball",
+                id: 25
+            })
+        );
+
+        cfru[SPECIES_OFFSET..SPECIES_OFFSET + 2].copy_from_slice(&922u16.to_le_bytes());
+        assert!(convert(&cfru, Format::Vanilla).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+//! PKHeX box reports, for checking edits made in PKHeX against the save as this
+//! tool reads it. A report is PKHeX's Box Data Report exported as CSV, or the
+//! same rows as a JSON array of objects.
+//!
+//! Only the `Box`, `Slot`, `Species`, `Nickname`, `Level`, `Nature` and
+//! `HeldItem` columns are read; anything else in the report is ignored. Column
+//! names are matched ignoring case, spaces and underscores. Box and slot are
+//! 1-based, as PKHeX shows them.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::ReportError;
+use crate::parser::{Location, PcBox, Pokemon};
+
+/// One boxed Pokemon as the report describes it. Empty cells are None and
+/// aren't compared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportEntry {
+    pub location: Location,
+    pub species: String,
+    pub nickname: Option<String>,
+    pub level: Option<u8>,
+    pub nature: Option<String>,
+    /// `NO_ITEM` when the report says the Pokemon holds nothing.
+    pub item: Option<String>,
+}
+
+/// What PKHeX writes for an empty hand.
+const NO_ITEM: &str = "(None)";
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Mismatch {
+    pub location: Location,
+    pub field: &'static str,
+    pub report: String,
+    pub save: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ReportDiff {
+    pub mismatches: Vec<Mismatch>,
+    /// Slots the report fills but are empty in the save.
+    pub only_in_report: Vec<Location>,
+    /// Slots filled in the save but missing from the report.
+    pub only_in_save: Vec<Location>,
+}
+
+/// Lowercase with spaces and underscores dropped, so "Held Item" and
+/// "HeldItem" name the same column.
+fn column_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Split CSV text into rows of fields. Quoted fields may hold commas, line
+/// breaks and doubled quotes.
+fn csv_rows(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// Report rows as column key to cell text.
+fn report_rows(text: &str) -> Result<Vec<HashMap<String, String>>, ReportError> {
+    if text.trim_start().starts_with('[') {
+        let rows: Vec<serde_json::Map<String, Value>> =
+            serde_json::from_str(text).map_err(|e| ReportError::Json(e.to_string()))?;
+        return Ok(rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(name, value)| {
+                        let cell = match value {
+                            Value::String(s) => s,
+                            Value::Null => String::new(),
+                            other => other.to_string(),
+                        };
+                        (column_key(&name), cell)
+                    })
+                    .collect()
+            })
+            .collect());
+    }
+    let mut rows = csv_rows(text).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .unwrap_or_default()
+        .iter()
+        .map(|h| column_key(h))
+        .collect();
+    Ok(rows
+        .map(|row| header.iter().cloned().zip(row).collect())
+        .collect())
+}
+
+fn entry_from_row(
+    row: &HashMap<String, String>,
+    number: usize,
+) -> Result<ReportEntry, ReportError> {
+    let cell = |column: &str| {
+        row.get(column)
+            .map(|cell| cell.trim())
+            .filter(|cell| !cell.is_empty())
+            .map(str::to_string)
+    };
+    let required = |column: &'static str| cell(column).ok_or(ReportError::MissingColumn(column));
+    let position = |column: &'static str| {
+        required(column)?
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n >= 1)
+            .ok_or_else(|| ReportError::BadRow {
+                row: number,
+                reason: format!("{} must be a number from 1", column),
+            })
+    };
+    let level = match cell("level") {
+        Some(level) => Some(level.parse().map_err(|_| ReportError::BadRow {
+            row: number,
+            reason: format!("bad level \"{}\"", level),
+        })?),
+        None => None,
+    };
+    Ok(ReportEntry {
+        location: Location::Box {
+            box_number: position("box")?,
+            slot: position("slot")? - 1,
+        },
+        species: required("species")?,
+        nickname: cell("nickname"),
+        level,
+        nature: cell("nature"),
+        item: cell("helditem").map(|item| match item.as_str() {
+            "None" => NO_ITEM.to_string(),
+            _ => item,
+        }),
+    })
+}
+
+/// Read a box report, CSV or JSON. Rows are numbered from 1, not counting
+/// the CSV header.
+pub fn parse_report(text: &str) -> Result<Vec<ReportEntry>, ReportError> {
+    report_rows(text)?
+        .iter()
+        .enumerate()
+        .map(|(i, row)| entry_from_row(row, i + 1))
+        .collect()
+}
+
+fn compare(entry: &ReportEntry, mon: &Pokemon, mismatches: &mut Vec<Mismatch>) {
+    let mut check = |field, report: &str, save: &str| {
+        if !report.eq_ignore_ascii_case(save) {
+            mismatches.push(Mismatch {
+                location: mon.location,
+                field,
+                report: report.to_string(),
+                save: save.to_string(),
+            });
+        }
+    };
+    check("species", &entry.species, &mon.species);
+    if let Some(nickname) = &entry.nickname {
+        check("nickname", nickname, &mon.nickname);
+    }
+    if let Some(level) = entry.level {
+        check("level", &level.to_string(), &mon.level.to_string());
+    }
+    if let Some(nature) = &entry.nature {
+        check("nature", nature, &mon.nature);
+    }
+    if let Some(item) = &entry.item {
+        check("item", item, mon.item.as_deref().unwrap_or(NO_ITEM));
+    }
+}
+
+/// Compare a report against the save's boxes, slot by slot.
+pub fn diff_report(report: &[ReportEntry], boxes: &[PcBox]) -> ReportDiff {
+    let boxed: Vec<&Pokemon> = boxes.iter().flat_map(|b| &b.pokemon).collect();
+    let mut mismatches = Vec::new();
+    let mut only_in_report = Vec::new();
+    for entry in report {
+        match boxed.iter().find(|mon| mon.location == entry.location) {
+            Some(mon) => compare(entry, mon, &mut mismatches),
+            None => only_in_report.push(entry.location),
+        }
+    }
+    let only_in_save = boxed
+        .iter()
+        .map(|mon| mon.location)
+        .filter(|&location| report.iter().all(|entry| entry.location != location))
+        .collect();
+    ReportDiff {
+        mismatches,
+        only_in_report,
+        only_in_save,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    /// A CSV report that matches the sample save's boxes exactly.
+    fn matching_csv(boxes: &[PcBox]) -> String {
+        let mut csv = "Position,Box,Slot,Species,Nickname,Level,Nature,Held Item\n".to_string();
+        for mon in boxes.iter().flat_map(|b| &b.pokemon) {
+            let Location::Box { box_number, slot } = mon.location else {
+                unreachable!()
+            };
+            csv.push_str(&format!(
+                "{}:{},{},{},{},\"{}\",{},{},{}\n",
+                box_number,
+                slot + 1,
+                box_number,
+                slot + 1,
+                mon.species,
+                mon.nickname,
+                mon.level,
+                mon.nature,
+                mon.item.as_deref().unwrap_or(NO_ITEM),
+            ));
+        }
+        csv
+    }
+
+    #[test]
+    fn test_csv_rows() {
+        let rows = csv_rows("\u{feff}a,\"b,\"\"c\"\"\"\r\n\n1,\"two\nlines\"");
+        assert_eq!(rows, [vec!["a", "b,\"c\""], vec!["1", "two\nlines"]]);
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let boxes = parser::parse_pc_boxes(&sections, &RADICAL_RED, false).expect("Boxes");
+        let csv = matching_csv(&boxes);
+
+        let report = parse_report(&csv).expect("Valid report");
+        let diff = diff_report(&report, &boxes);
+        assert!(diff.mismatches.is_empty(), "{:?}", diff.mismatches);
+        assert!(diff.only_in_report.is_empty() && diff.only_in_save.is_empty());
+
+        // Level Cubchoo up in "PKHeX", drop the second row and add a Pokemon
+        // to an empty slot
+        let mut lines: Vec<String> = csv.lines().map(str::to_string).collect();
+        lines[1] = lines[1].replace(",28,", ",29,");
+        lines.remove(2);
+        lines.push("1:4,1,4,Pikachu,PIKACHU,5,Hardy,(None)".to_string());
+        let report = parse_report(&lines.join("\n")).expect("Valid report");
+        let diff = diff_report(&report, &boxes);
+
+        let cubchoo = boxes[0].pokemon[0].location;
+        assert_eq!(
+            diff.mismatches,
+            [Mismatch {
+                location: cubchoo,
+                field: "level",
+                report: "29".to_string(),
+                save: "28".to_string(),
+            }]
+        );
+        let slot = |slot| Location::Box {
+            box_number: 1,
+            slot,
+        };
+        assert_eq!(diff.only_in_report, [slot(3)]);
+        assert_eq!(diff.only_in_save, [slot(1)]);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json = r#"[{"Box": 1, "Slot": 1, "Species": "Cubchoo", "Level": 28,
+            "HeldItem": "Aspear Berry", "Nickname": null}]"#;
+        let report = parse_report(json).expect("Valid report");
+        assert_eq!(
+            report,
+            [ReportEntry {
+                location: Location::Box {
+                    box_number: 1,
+                    slot: 0,
+                },
+                species: "Cubchoo".to_string(),
+                nickname: None,
+                level: Some(28),
+                nature: None,
+                item: Some("Aspear Berry".to_string()),
+            }]
+        );
+
+        assert_eq!(
+            parse_report("Box,Slot\n1,1"),
+            Err(ReportError::MissingColumn("species"))
+        );
+        assert_eq!(
+            parse_report("Box,Slot,Species\n1,0,Cubchoo"),
+            Err(ReportError::BadRow {
+                row: 1,
+                reason: "slot must be a number from 1".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Save layouts per game variant. Supporting a new variant means adding a
+//! profile here rather than changing offsets throughout the parser.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bag::Pocket;
+use crate::pk3::Format;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Game {
+    #[default]
+    RadicalRed,
+    FireRedLeafGreen,
+    /// Emerald and hacks built on it.
+    Emerald,
+}
+
+impl Game {
+    pub fn profile(self) -> &'static GameProfile {
+        match self {
+            Game::RadicalRed => &RADICAL_RED,
+            Game::FireRedLeafGreen => &FIRE_RED_LEAF_GREEN,
+            Game::Emerald => &EMERALD,
+        }
+    }
+}
+
+/// Offsets within a party Pokemon struct. Substructure offsets are from the
+/// start of the struct.
+pub struct PokemonLayout {
+    pub size: usize,
+    /// species(u16), item(u16), exp(u32), ppBonuses, friendship
+    pub growth: usize,
+    /// move1-4(u16 each), pp1-4
+    pub attacks: usize,
+    /// HP, Atk, Def, Spe, SpA, SpD EVs, then contest stats
+    pub evs: usize,
+    /// pokerus, metLocation, origins(u16), IV word(u32), ribbons(u32)
+    pub misc: usize,
+    /// Party-only battle data: status condition(u32), level, current HP(u16),
+    /// max HP(u16), then the other stats.
+    pub status: usize,
+    pub level: usize,
+    pub hp: usize,
+}
+
+/// Where an item pocket's slots are stored.
+pub struct PocketLayout {
+    pub pocket: Pocket,
+    pub store: PocketStore,
+    pub offset: usize,
+    pub capacity: usize,
+}
+
+/// The block of save data a pocket's offset is relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PocketStore {
+    /// A section of the active save slot.
+    Section(u16),
+    /// A flash sector outside both save slots, by index from the start of
+    /// the save. It has no footer and isn't kept twice like the slots are.
+    FlashSector(usize),
+}
+
+pub struct GameProfile {
+    /// Section holding the trainer name and ID (SaveBlock2).
+    pub trainer_section: u16,
+    pub party_section: u16,
+    pub party_count_offset: usize,
+    pub party_offset: usize,
+    pub pokemon: PokemonLayout,
+    /// PC storage runs across these sections, inclusive.
+    pub pc_sections: (u16, u16),
+    pub box_count: usize,
+    pub box_capacity: usize,
+    pub box_pokemon_size: usize,
+    /// Offset of the daycare within SaveBlock1, where known.
+    pub daycare_offset: Option<usize>,
+    /// Bytes covered by each section's checksum, indexed by section ID.
+    pub section_sizes: [usize; 14],
+    /// Offset in the trainer section of the key money is XORed with.
+    pub security_key_offset: usize,
+    /// Offset of the money (u32) within SaveBlock1.
+    pub money_offset: usize,
+    /// Offset of the event flags within SaveBlock1, and the flag of the first
+    /// badge. The other seven badges follow it in order.
+    pub flags_offset: usize,
+    pub badge_flags_start: usize,
+    /// Section and offsets of the Pokedex caught and seen flags, one bit per
+    /// national dex number starting from Bulbasaur, and how many species
+    /// the dex covers.
+    pub dex_section: u16,
+    pub dex_caught_offset: usize,
+    pub dex_seen_offset: usize,
+    pub dex_species_count: usize,
+    /// Offset of the PC's item storage in section 1, and how many
+    /// item slots it has.
+    pub pc_items_offset: usize,
+    pub pc_items_capacity: usize,
+    /// Offset of the mail array within SaveBlock1.
+    pub mail_offset: usize,
+    /// Offset within SaveBlock1 of the VS Seeker step counter, which the
+    /// rematch table follows, where known.
+    pub rematch_offset: Option<usize>,
+    /// Offset in the trainer section of the link minigame records, where known.
+    pub minigames_offset: Option<usize>,
+    /// Bag pockets, in the order the game shows them.
+    pub bag_pockets: &'static [PocketLayout],
+    /// Whether Pokemon structs are stored encrypted and shuffled (vanilla) or
+    /// in CFRU's fixed layout.
+    pub pokemon_format: Format,
+}
+
+/// A vanilla bag pocket. These all sit in the first section of SaveBlock1.
+const fn pocket(pocket: Pocket, offset: usize, capacity: usize) -> PocketLayout {
+    PocketLayout {
+        pocket,
+        store: PocketStore::Section(1),
+        offset,
+        capacity,
+    }
+}
+
+/// A CFRU pocket kept in flash sector 30, past the Hall of Fame.
+const fn cfru_pocket(pocket: Pocket, offset: usize, capacity: usize) -> PocketLayout {
+    PocketLayout {
+        pocket,
+        store: PocketStore::FlashSector(30),
+        offset,
+        capacity,
+    }
+}
+
+/// CFRU/Radical Red: fixed substructure order, no encryption, and compressed
+/// 58-byte box Pokemon. Only the first 18 boxes fit in sections 5-13 before
+/// the box names start.
+pub const RADICAL_RED: GameProfile = GameProfile {
+    trainer_section: 0,
+    party_section: 1,
+    party_count_offset: 0x0034,
+    party_offset: 0x0038,
+    pokemon: PokemonLayout {
+        size: 100,
+        growth: 32,
+        attacks: 44,
+        evs: 56,
+        misc: 68,
+        status: 80,
+        level: 84,
+        hp: 86,
+    },
+    pc_sections: (5, 13),
+    box_count: 18,
+    box_capacity: 30,
+    box_pokemon_size: 58,
+    // Only where SaveBlock1 is laid out as CFRU checksums it; see
+    // parser::profile_save_block1.
+    daycare_offset: Some(0x2F80),
+    // CFRU checksums all of sections 1-3, which it expands SaveBlock1 into,
+    // and only the part of section 13 it still uses.
+    section_sizes: [
+        0xF24, 0xFF0, 0xFF0, 0xFF0, 0xD98, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80,
+        0x450,
+    ],
+    security_key_offset: 0x0F20,
+    money_offset: 0x0290,
+    flags_offset: 0x0EE0,
+    badge_flags_start: 0x820,
+    // CFRU's expanded dex outgrows SaveBlock2, so it keeps its flags in section 1
+    dex_section: 1,
+    dex_caught_offset: 0x03B4,
+    dex_seen_offset: 0x0310,
+    dex_species_count: 1025,
+    pc_items_offset: 0x0298,
+    pc_items_capacity: 30,
+    mail_offset: 0x2CD0,
+    rematch_offset: Some(0x0638),
+    minigames_offset: Some(0x0AF0),
+    // CFRU moves the bag out of SaveBlock1. The items pocket's size isn't
+    // known, so it is read up to the end of its section. The other pockets
+    // sit back to back in flash sector 30, each running up to the next; the
+    // berries are followed by other data at 0x710.
+    bag_pockets: &[
+        PocketLayout {
+            pocket: Pocket::Items,
+            store: PocketStore::Section(13),
+            offset: 0x0AD8,
+            capacity: (0xFF0 - 0x0AD8) / 4,
+        },
+        cfru_pocket(Pocket::KeyItems, 0x01F0, 75),
+        cfru_pocket(Pocket::Balls, 0x031C, 50),
+        cfru_pocket(Pocket::TmsHms, 0x03E4, 128),
+        cfru_pocket(Pocket::Berries, 0x05E4, 75),
+    ],
+    pokemon_format: Format::Cfru,
+};
+
+/// Unmodified FireRed/LeafGreen: same section layout, but Pokemon are
+/// encrypted 100-byte (party) and 80-byte (box) structs, and there are 14 boxes.
+pub const FIRE_RED_LEAF_GREEN: GameProfile = GameProfile {
+    box_count: 14,
+    box_pokemon_size: 80,
+    daycare_offset: Some(0x2F80),
+    section_sizes: [
+        0xF24, 0xF80, 0xF80, 0xF80, 0xD98, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80,
+        0x7D0,
+    ],
+    // The game also keeps two copies of the seen flags in SaveBlock1, but
+    // they always match the ones in SaveBlock2.
+    dex_section: 0,
+    dex_caught_offset: 0x0028,
+    dex_seen_offset: 0x005C,
+    dex_species_count: 386,
+    bag_pockets: &[
+        pocket(Pocket::Items, 0x0310, 42),
+        pocket(Pocket::KeyItems, 0x03B8, 30),
+        pocket(Pocket::Balls, 0x0430, 13),
+        pocket(Pocket::TmsHms, 0x0464, 58),
+        pocket(Pocket::Berries, 0x054C, 43),
+    ],
+    pokemon_format: Format::Vanilla,
+    ..RADICAL_RED
+};
+
+/// Emerald: vanilla Pokemon structs like FRLG, but SaveBlock1 keeps more
+/// before the party, so it starts at 0x238 instead of 0x38. The security key
+/// sits where FRLG has the game code. Ruby/Sapphire share the party offset but
+/// not the daycare, mail, flag or bag offsets, so they aren't covered.
+pub const EMERALD: GameProfile = GameProfile {
+    party_count_offset: 0x0234,
+    party_offset: 0x0238,
+    daycare_offset: Some(0x3030),
+    section_sizes: [
+        0xF2C, 0xF80, 0xF80, 0xF80, 0xF08, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80, 0xF80,
+        0x7D0,
+    ],
+    security_key_offset: 0x00AC,
+    money_offset: 0x0490,
+    flags_offset: 0x1270,
+    badge_flags_start: 0x867,
+    pc_items_offset: 0x0498,
+    pc_items_capacity: 50,
+    mail_offset: 0x2BE0,
+    // Emerald replaces the VS Seeker with Match Call and moves the minigame
+    // records; neither has been mapped yet.
+    rematch_offset: None,
+    minigames_offset: None,
+    bag_pockets: &[
+        pocket(Pocket::Items, 0x0560, 30),
+        pocket(Pocket::KeyItems, 0x05D8, 30),
+        pocket(Pocket::Balls, 0x0650, 16),
+        pocket(Pocket::TmsHms, 0x0690, 64),
+        pocket(Pocket::Berries, 0x0790, 46),
+    ],
+    ..FIRE_RED_LEAF_GREEN
+};
@@ -0,0 +1,124 @@
+//! How far through the story a save is, going by badges and key items.
+
+use serde::Serialize;
+
+use crate::bag::{self, Pocket};
+use crate::error::ParseError;
+use crate::parser::Section;
+use crate::profile::GameProfile;
+use crate::trainer;
+
+/// Key items and HMs that mark story progress, roughly in the order they're
+/// picked up in FireRed.
+const MILESTONE_ITEMS: [&str; 14] = [
+    "HM01",
+    "Bicycle",
+    "HM05",
+    "HM02",
+    "Poke Flute",
+    "Silph Scope",
+    "Card Key",
+    "HM03",
+    "HM04",
+    "Tri-Pass",
+    "Rainbow Pass",
+    "HM06",
+    "HM07",
+    "HM08",
+];
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    pub item: &'static str,
+    /// None when the game's key item and TM pockets can't be read.
+    pub owned: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StoryProgress {
+    pub badge_count: usize,
+    pub milestones: Vec<Milestone>,
+    /// The entry of `level_caps` for the current badge count, if there is one.
+    pub level_cap: Option<u8>,
+}
+
+/// Story progress of a save. `level_caps` lists the cap before the first
+/// badge, then after each badge, so the current cap is the one at the badge count.
+pub fn story_progress(
+    raw: &[u8],
+    sections: &[Section],
+    profile: &GameProfile,
+    level_caps: &[u8],
+) -> Result<StoryProgress, ParseError> {
+    let info = trainer::parse_trainer_info(sections, profile)?;
+    let badge_count = info.badges.iter().filter(|&&badge| badge).count();
+
+    let inventory = bag::parse_bag(raw, sections, profile)?;
+    let item_pockets: Vec<_> = inventory
+        .pockets
+        .iter()
+        .filter(|p| matches!(p.pocket, Pocket::KeyItems | Pocket::TmsHms))
+        .collect();
+    let milestones = MILESTONE_ITEMS
+        .iter()
+        .map(|&item| Milestone {
+            item,
+            // Both pockets are needed to tell whether an item is missing
+            owned: (item_pockets.len() == 2).then(|| {
+                item_pockets
+                    .iter()
+                    .any(|p| p.items.iter().any(|i| i.name == item))
+            }),
+        })
+        .collect();
+
+    Ok(StoryProgress {
+        badge_count,
+        milestones,
+        level_cap: level_caps.get(badge_count).copied(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::{self, IoMode};
+
+    #[test]
+    fn test_story_progress() {
+        let raw = reader::read_save(TEST_SAV, IoMode::Direct).expect("Failed to read save");
+        let sections = parser::active_slot(&raw).expect("Failed to load save");
+        let progress =
+            story_progress(&raw, &sections, &RADICAL_RED, &[12, 20, 26]).expect("Failed to parse");
+        assert_eq!(progress.badge_count, 1);
+        assert_eq!(progress.level_cap, Some(20));
+        // One badge in, none of the milestone items have been picked up yet
+        assert!(progress.milestones.iter().all(|m| m.owned == Some(false)));
+
+        let uncapped =
+            story_progress(&raw, &sections, &RADICAL_RED, &[12]).expect("Failed to parse");
+        assert_eq!(uncapped.level_cap, None);
+    }
+
+    #[test]
+    fn test_radical_red_milestones() {
+        let mut raw = reader::read_save(TEST_SAV, IoMode::Direct).expect("Failed to read save");
+        // Append a Bicycle to the key items and HM01 to the TMs, in flash sector 30
+        let sector = 30 * parser::SECTION_SIZE;
+        raw[sector + 0x0210..sector + 0x0214].copy_from_slice(&[0x68, 0x01, 0x01, 0x00]);
+        raw[sector + 0x041C..sector + 0x0420].copy_from_slice(&[0x53, 0x01, 0x01, 0x00]);
+        let sections = parser::active_slot(&raw).expect("Failed to load save");
+
+        let progress = story_progress(&raw, &sections, &RADICAL_RED, &[]).expect("Failed to parse");
+        let owned: Vec<_> = progress
+            .milestones
+            .iter()
+            .filter(|m| m.owned == Some(true))
+            .map(|m| m.item)
+            .collect();
+        assert_eq!(owned, ["HM01", "Bicycle"]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Stat projection: each party member's stats at level 50 and 100 with its
+//! current IVs, EVs and nature.
+
+use serde::Serialize;
+
+use crate::parser::{self, Pokemon};
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct StatProjection {
+    /// Party slot, 0-5.
+    pub slot: usize,
+    pub nickname: String,
+    pub species: String,
+    pub level: u8,
+    /// Stats in Showdown order (HP, Atk, Def, SpA, SpD, Spe). The projections
+    /// are None when the species' base stats aren't known to match the game's.
+    pub current: Option<[u16; 6]>,
+    pub level_50: Option<[u16; 6]>,
+    pub level_100: Option<[u16; 6]>,
+}
+
+/// Project every party member's stats. Eggs are skipped.
+pub fn project_stats(party: &[Pokemon]) -> Vec<StatProjection> {
+    party
+        .iter()
+        .enumerate()
+        .filter(|(_, mon)| !mon.is_egg)
+        .map(|(slot, mon)| StatProjection {
+            slot,
+            nickname: mon.nickname.clone(),
+            species: mon.species.clone(),
+            level: mon.level,
+            current: mon.stats,
+            level_50: parser::stats_at_level(mon, 50),
+            level_100: parser::stats_at_level(mon, 100),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::rr_party;
+
+    #[test]
+    fn test_project_stats() {
+        let mut party = rr_party();
+        party[1].is_egg = true;
+
+        let projections = project_stats(&party);
+        assert_eq!(projections.len(), 5);
+        assert_eq!(projections[1].slot, 2);
+        assert!(projections.iter().all(|p| p.current.is_some()));
+
+        // Only Pawmo, Luxio and Cetoddle have base stats known to match RR's
+        let projected: Vec<_> = projections
+            .iter()
+            .filter(|p| p.level_50.is_some())
+            .collect();
+        assert_eq!(projected.len(), 3);
+        for p in projected {
+            let (current, lv50, lv100) = (
+                p.current.unwrap(),
+                p.level_50.unwrap(),
+                p.level_100.unwrap(),
+            );
+            assert!((0..6).all(|i| current[i] <= lv50[i] && lv50[i] < lv100[i]));
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::ParseError;
+
+const MAX_ATTEMPTS: u32 = 6;
+const INITIAL_BACKOFF_MS: u64 = 50;
+
+thread_local! {
+    /// Bytes read from disk on this thread since the last `take_bytes_read`.
+    static BYTES_READ: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Count `len` bytes towards this thread's read total.
+pub(crate) fn count_bytes_read(len: usize) {
+    BYTES_READ.set(BYTES_READ.get() + len as u64);
+}
+
+/// Bytes read on this thread since the last call, resetting the count.
+pub(crate) fn take_bytes_read() -> u64 {
+    BYTES_READ.take()
+}
+
+/// `fs::read`, counting what it reads.
+fn read_counted(path: &str) -> std::io::Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    count_bytes_read(data.len());
+    Ok(data)
+}
+
+/// How save files are read from disk.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IoMode {
+    /// A single plain read.
+    #[default]
+    Direct,
+    /// Retry with backoff and only accept the data once two consecutive reads
+    /// match, so half-synced files in OneDrive/Dropbox folders or files held
+    /// by an AV scanner aren't parsed mid-write.
+    SyncSafe,
+}
+
+pub fn read_save(path: &str, mode: IoMode) -> Result<Vec<u8>, ParseError> {
+    match mode {
+        IoMode::Direct => read_counted(path).map_err(|e| ParseError::Io(e.to_string())),
+        IoMode::SyncSafe => read_stable(path),
+    }
+}
+
+fn read_stable(path: &str) -> Result<Vec<u8>, ParseError> {
+    let mut previous: Option<Vec<u8>> = None;
+    let mut last_error = String::new();
+    let mut backoff = Duration::from_millis(INITIAL_BACKOFF_MS);
+
+    for attempt in 0..MAX_ATTEMPTS {
+        if attempt > 0 {
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+        match read_counted(path) {
+            Ok(data) => {
+                if previous.as_ref() == Some(&data) {
+                    return Ok(data);
+                }
+                previous = Some(data);
+            }
+            Err(e) => {
+                last_error = e.to_string();
+                previous = None;
+            }
+        }
+    }
+
+    if previous.is_some() {
+        Err(ParseError::Unstable)
+    } else {
+        Err(ParseError::Io(last_error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+
+    #[test]
+    fn test_sync_safe_matches_direct_read() {
+        let direct = read_save(TEST_SAV, IoMode::Direct).expect("Direct read failed");
+        let stable = read_save(TEST_SAV, IoMode::SyncSafe).expect("Sync-safe read failed");
+        assert_eq!(direct, stable);
+    }
+
+    #[test]
+    fn test_counts_every_read() {
+        take_bytes_read();
+        let direct = read_save(TEST_SAV, IoMode::Direct).expect("Direct read failed");
+        assert_eq!(take_bytes_read(), direct.len() as u64);
+        // A stable file is accepted on the second read
+        read_save(TEST_SAV, IoMode::SyncSafe).expect("Sync-safe read failed");
+        assert_eq!(take_bytes_read(), 2 * direct.len() as u64);
+        assert_eq!(take_bytes_read(), 0);
+    }
+}
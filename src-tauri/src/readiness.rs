@@ -0,0 +1,117 @@
+//! Pre-battle sanity check over the party.
+
+use serde::Serialize;
+
+use crate::parser::{Pokemon, Status};
+
+/// HP below this share of max HP (in percent) is flagged as low.
+const LOW_HP_PERCENT: u32 = 50;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+    Fainted,
+    Status { status: Status },
+    LowHp { current_hp: u16, max_hp: u16 },
+    NoItem,
+    OverLevelCap { level: u8, level_cap: u8 },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReadinessWarning {
+    /// Party slot, 0-5.
+    pub slot: usize,
+    pub nickname: String,
+    pub issue: Issue,
+}
+
+/// Issues with a single party member. Fainted Pokemon only report fainting,
+/// since their status and HP don't matter until they're revived.
+fn check_pokemon(mon: &Pokemon, level_cap: Option<u8>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    match (mon.current_hp, mon.max_hp) {
+        (Some(0), _) => return vec![Issue::Fainted],
+        (Some(current_hp), Some(max_hp))
+            if (current_hp as u32) * 100 < (max_hp as u32) * LOW_HP_PERCENT =>
+        {
+            issues.push(Issue::LowHp { current_hp, max_hp });
+        }
+        _ => {}
+    }
+    if let Some(status) = mon.status.filter(|&s| s != Status::Healthy) {
+        issues.push(Issue::Status { status });
+    }
+    if mon.item.is_none() {
+        issues.push(Issue::NoItem);
+    }
+    if let Some(level_cap) = level_cap.filter(|&cap| mon.level > cap) {
+        issues.push(Issue::OverLevelCap {
+            level: mon.level,
+            level_cap,
+        });
+    }
+    issues
+}
+
+/// Flag every party member that isn't ready to battle. Eggs are skipped.
+pub fn team_readiness(party: &[Pokemon], level_cap: Option<u8>) -> Vec<ReadinessWarning> {
+    party
+        .iter()
+        .enumerate()
+        .filter(|(_, mon)| !mon.is_egg)
+        .flat_map(|(slot, mon)| {
+            check_pokemon(mon, level_cap)
+                .into_iter()
+                .map(move |issue| ReadinessWarning {
+                    slot,
+                    nickname: mon.nickname.clone(),
+                    issue,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::rr_party;
+
+    #[test]
+    fn test_team_readiness() {
+        let mut party = rr_party();
+
+        // Everyone is healthy and at full HP; only Kaeman holds an item.
+        let warnings = team_readiness(&party, None);
+        assert_eq!(warnings.len(), 5);
+        assert!(warnings
+            .iter()
+            .all(|w| w.issue == Issue::NoItem && w.slot != 3));
+
+        let capped = team_readiness(&party, Some(27));
+        assert_eq!(
+            capped
+                .iter()
+                .filter(|w| matches!(w.issue, Issue::OverLevelCap { .. }))
+                .count(),
+            6
+        );
+
+        party[0].current_hp = Some(0);
+        party[1].current_hp = Some(20);
+        party[1].status = Some(Status::Burn);
+        assert_eq!(check_pokemon(&party[0], None), [Issue::Fainted]);
+        assert_eq!(
+            check_pokemon(&party[1], None),
+            [
+                Issue::LowHp {
+                    current_hp: 20,
+                    max_hp: 104
+                },
+                Issue::Status {
+                    status: Status::Burn
+                },
+                Issue::NoItem,
+            ]
+        );
+    }
+}
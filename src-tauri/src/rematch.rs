@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use crate::error::ParseError;
+use crate::parser::{self, bytes_at, read_u16, Section};
+use crate::profile::GameProfile;
+
+/// The rematch table follows the VS Seeker step counter.
+const REMATCHES_OFFSET: usize = 2;
+const REMATCH_COUNT: usize = 100;
+/// The VS Seeker can be used again once the step counter reaches this value.
+const VS_SEEKER_CHARGED_STEPS: u16 = 100;
+
+/// Trainer-defeated flags start at event flag 0x500.
+const TRAINER_FLAGS_START: usize = 0x500;
+const TRAINER_COUNT: usize = 743;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RematchState {
+    pub vs_seeker_steps: u16,
+    pub vs_seeker_charged: bool,
+    /// Indices into the game's rematch table whose trainers are waiting for a rematch.
+    pub pending_rematches: Vec<usize>,
+    pub defeated_trainers: Vec<u16>,
+}
+
+pub fn parse_rematch_state(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<RematchState, ParseError> {
+    let offset = profile.rematch_offset.ok_or(ParseError::Unsupported {
+        feature: "VS Seeker",
+    })?;
+    let block = parser::profile_save_block1(sections, profile)?;
+
+    let vs_seeker_steps = read_u16(&block, offset)?;
+    let pending_rematches = bytes_at(&block, offset + REMATCHES_OFFSET, REMATCH_COUNT)?
+        .iter()
+        .enumerate()
+        .filter(|(_, &state)| state != 0)
+        .map(|(i, _)| i)
+        .collect();
+
+    let trainer_flags = bytes_at(
+        &block,
+        profile.flags_offset + TRAINER_FLAGS_START / 8,
+        TRAINER_COUNT.div_ceil(8),
+    )?;
+    let defeated_trainers = (0..TRAINER_COUNT)
+        .filter(|&id| trainer_flags[id / 8] & (1 << (id % 8)) != 0)
+        .map(|id| id as u16)
+        .collect();
+
+    Ok(RematchState {
+        vs_seeker_steps,
+        vs_seeker_charged: vs_seeker_steps >= VS_SEEKER_CHARGED_STEPS,
+        pending_rematches,
+        defeated_trainers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::{EMERALD, RADICAL_RED};
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_parse_rematch_state() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let state =
+            parse_rematch_state(&sections, &RADICAL_RED).expect("Failed to parse rematch state");
+        assert_eq!(state.vs_seeker_steps, 100);
+        assert!(state.vs_seeker_charged);
+        assert!(state.pending_rematches.is_empty());
+        assert_eq!(state.defeated_trainers[..6], [4, 6, 21, 44, 45, 46]);
+        // Brock, matching the one badge on the trainer card
+        assert!(state.defeated_trainers.contains(&414));
+
+        assert_eq!(
+            parse_rematch_state(&sections, &EMERALD).unwrap_err(),
+            ParseError::Unsupported {
+                feature: "VS Seeker"
+            }
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! User-defined warning rules, kept in settings and checked against the party
+//! after every parse.
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::Pokemon;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    /// Warn about party members more than `max_gap` levels below `level_cap`.
+    UnderLevelCap { level_cap: u8, max_gap: u8 },
+    /// Warn when the party has fewer than `size` Pokemon.
+    MinPartySize { size: usize },
+    /// Warn when nobody in the party knows `move_name`, e.g. an HM.
+    MoveKnown { move_name: String },
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RuleWarning {
+    /// Index of the broken rule in the user's rule list.
+    pub rule_index: usize,
+    pub rule: Rule,
+    /// The party slot at fault, for rules about a single Pokemon.
+    pub slot: Option<usize>,
+    pub nickname: Option<String>,
+}
+
+/// Party slots that break `rule`, or `Some(None)` if it's broken by the party
+/// as a whole.
+fn check_rule(rule: &Rule, party: &[(usize, &Pokemon)]) -> Vec<Option<usize>> {
+    match rule {
+        Rule::UnderLevelCap { level_cap, max_gap } => party
+            .iter()
+            .filter(|(_, mon)| (mon.level as u16) + (*max_gap as u16) < *level_cap as u16)
+            .map(|&(slot, _)| Some(slot))
+            .collect(),
+        Rule::MinPartySize { size } if party.len() < *size => vec![None],
+        Rule::MoveKnown { move_name }
+            if !party.iter().any(|(_, mon)| {
+                mon.moves
+                    .iter()
+                    .any(|m| m.name.eq_ignore_ascii_case(move_name))
+            }) =>
+        {
+            vec![None]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Check every rule against the party. Eggs don't count as party members.
+pub fn evaluate(rules: &[Rule], party: &[Pokemon]) -> Vec<RuleWarning> {
+    let members: Vec<(usize, &Pokemon)> = party
+        .iter()
+        .enumerate()
+        .filter(|(_, mon)| !mon.is_egg)
+        .collect();
+    let mut warnings = Vec::new();
+    for (rule_index, rule) in rules.iter().enumerate() {
+        for slot in check_rule(rule, &members) {
+            warnings.push(RuleWarning {
+                rule_index,
+                rule: rule.clone(),
+                slot,
+                nickname: slot.map(|slot| party[slot].nickname.clone()),
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::rr_party;
+
+    #[test]
+    fn test_evaluate_rules() {
+        let mut party = rr_party();
+        party[4].level = 25;
+
+        let rules = [
+            Rule::UnderLevelCap {
+                level_cap: 30,
+                max_gap: 2,
+            },
+            Rule::MinPartySize { size: 6 },
+            Rule::MoveKnown {
+                move_name: "Surf".to_string(),
+            },
+            Rule::MoveKnown {
+                move_name: "rest".to_string(),
+            },
+        ];
+        let warnings = evaluate(&rules, &party);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!((warnings[0].rule_index, warnings[0].slot), (0, Some(4)));
+        assert_eq!(warnings[0].nickname.as_deref(), Some("Sparky"));
+        assert_eq!((warnings[1].rule_index, warnings[1].slot), (2, None));
+
+        party[0].is_egg = true;
+        assert!(evaluate(&rules[1..2], &party)
+            .iter()
+            .any(|w| w.rule_index == 0 && w.slot.is_none()));
+    }
+}
@@ -0,0 +1,164 @@
+//! Builds synthetic CFRU save images in memory, used for the demo mode and tests.
+
+use crate::charmap::encode_gen3_string;
+use crate::error::EncodeError;
+use crate::parser::{
+    section_checksum, SECTION_CHECKSUM_OFFSET, SECTION_COUNT, SECTION_SIGNATURE, SECTION_SIZE,
+    SLOT_SIZE,
+};
+use crate::profile::RADICAL_RED;
+
+/// All six IVs at 31, normal ability.
+const PERFECT_IVS: u32 = 0x3FFF_FFFF;
+const LANGUAGE_ENGLISH: u8 = 2;
+const DEMO_OT_ID: u32 = 0x0000_3039;
+const DEMO_OT_NAME: &str = "Demo";
+/// Game code CFRU inherits from FireRed, so synthetic saves detect as Radical Red.
+const GAME_CODE_FRLG: u32 = 1;
+
+/// A party member to write into a synthetic save.
+pub struct SynthMon {
+    pub personality: u32,
+    pub nickname: &'static str,
+    pub species: u16,
+    pub item: u16,
+    pub level: u8,
+    pub moves: [u16; 4],
+}
+
+/// The team loaded by demo mode.
+#[rustfmt::skip]
+pub const DEMO_PARTY: [SynthMon; 6] = [
+    SynthMon { personality: 25010, nickname: "Blaze", species: 6, item: 215, level: 50, moves: [53, 356, 370, 395] },
+    SynthMon { personality: 50015, nickname: "Shelly", species: 9, item: 209, level: 50, moves: [57, 58, 359, 449] },
+    SynthMon { personality: 75020, nickname: "Bulby", species: 3, item: 205, level: 50, moves: [202, 188, 79, 89] },
+    SynthMon { personality: 100013, nickname: "Sparky", species: 25, item: 202, level: 50, moves: [344, 231, 98, 85] },
+    SynthMon { personality: 125010, nickname: "Boo", species: 94, item: 678, level: 50, moves: [247, 531, 374, 95] },
+    SynthMon { personality: 150003, nickname: "Snorlax", species: 143, item: 200, level: 50, moves: [34, 156, 242, 89] },
+];
+
+/// Encode a name into its field, padding with the 0xFF terminator.
+fn encode_name(name: &str, out: &mut [u8]) -> Result<(), EncodeError> {
+    out.copy_from_slice(&encode_gen3_string(name, out.len())?);
+    Ok(())
+}
+
+/// Write a party Pokemon in the CFRU layout (fixed substructure order, no encryption).
+fn write_pokemon(mon: &SynthMon, out: &mut [u8]) -> Result<(), EncodeError> {
+    out[0..4].copy_from_slice(&mon.personality.to_le_bytes());
+    out[4..8].copy_from_slice(&DEMO_OT_ID.to_le_bytes());
+    encode_name(mon.nickname, &mut out[8..18])?;
+    out[18] = LANGUAGE_ENGLISH;
+    encode_name(DEMO_OT_NAME, &mut out[20..27])?;
+    out[32..34].copy_from_slice(&mon.species.to_le_bytes());
+    out[34..36].copy_from_slice(&mon.item.to_le_bytes());
+    for (i, m) in mon.moves.iter().enumerate() {
+        out[44 + i * 2..46 + i * 2].copy_from_slice(&m.to_le_bytes());
+    }
+    out[72..76].copy_from_slice(&PERFECT_IVS.to_le_bytes());
+    out[84] = mon.level;
+    out[85] = 0xFF;
+    Ok(())
+}
+
+/// Build a full 128KiB save image whose first slot holds the given party.
+/// The second slot is left blank so the first one is always the active slot.
+/// Fails if a nickname can't be written in its 10-byte field.
+pub fn build_save(party: &[SynthMon]) -> Result<Vec<u8>, EncodeError> {
+    let mut raw = vec![0u8; SLOT_SIZE * 2];
+
+    for id in 0..SECTION_COUNT {
+        let section = &mut raw[id * SECTION_SIZE..(id + 1) * SECTION_SIZE];
+        section[0xFF4..0xFF6].copy_from_slice(&(id as u16).to_le_bytes());
+        section[0xFF8..0xFFC].copy_from_slice(&SECTION_SIGNATURE.to_le_bytes());
+        section[0xFFC..0x1000].copy_from_slice(&1u32.to_le_bytes());
+    }
+
+    let sec0 = &mut raw[..SECTION_SIZE];
+    encode_name(DEMO_OT_NAME, &mut sec0[0..8])?;
+    sec0[0x0A..0x0E].copy_from_slice(&DEMO_OT_ID.to_le_bytes());
+    sec0[0xAC..0xB0].copy_from_slice(&GAME_CODE_FRLG.to_le_bytes());
+
+    let profile = &RADICAL_RED;
+    let sec1 = &mut raw[SECTION_SIZE..SECTION_SIZE * 2];
+    let count = party.len().min(6);
+    let count_off = profile.party_count_offset;
+    sec1[count_off..count_off + 4].copy_from_slice(&(count as u32).to_le_bytes());
+    for (i, mon) in party.iter().take(count).enumerate() {
+        let off = profile.party_offset + i * profile.pokemon.size;
+        write_pokemon(mon, &mut sec1[off..off + profile.pokemon.size])?;
+    }
+
+    for (id, size) in profile.section_sizes.iter().enumerate() {
+        let section = &mut raw[id * SECTION_SIZE..(id + 1) * SECTION_SIZE];
+        let checksum = section_checksum(&section[..*size]);
+        section[SECTION_CHECKSUM_OFFSET..SECTION_CHECKSUM_OFFSET + 2]
+            .copy_from_slice(&checksum.to_le_bytes());
+    }
+
+    Ok(raw)
+}
+
+pub fn demo_save() -> Result<Vec<u8>, EncodeError> {
+    build_save(&DEMO_PARTY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+    use crate::profile::Game;
+
+    #[test]
+    fn test_demo_save_parses() {
+        let raw = demo_save().expect("Demo save should build");
+        let sections = parser::active_slot(&raw).expect("Demo save should load");
+        assert_eq!(parser::detect_game(&sections), Ok(Game::RadicalRed));
+        assert_eq!(parser::corrupt_sections(&sections, &RADICAL_RED), []);
+        let party = parser::parse_party(&sections, &RADICAL_RED, false)
+            .expect("Failed to parse demo party");
+
+        let names: Vec<(&str, &str)> = party
+            .iter()
+            .map(|p| (p.nickname.as_str(), p.species.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("Blaze", "Charizard"),
+                ("Shelly", "Blastoise"),
+                ("Bulby", "Venusaur"),
+                ("Sparky", "Pikachu"),
+                ("Boo", "Gengar"),
+                ("Snorlax", "Snorlax"),
+            ]
+        );
+        assert!(party.iter().all(|p| !p.is_traded));
+        assert_eq!(party[0].nature, "Timid");
+        assert_eq!(party[0].item.as_deref(), Some("Charcoal"));
+        let moves: Vec<&str> = party[0].moves.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            moves,
+            ["Flamethrower", "Air Slash", "Dragon Pulse", "Roost"]
+        );
+    }
+
+    #[test]
+    fn test_other_trainer_marks_party_traded() {
+        let mut raw = demo_save().expect("Demo save should build");
+        raw[0x0A] ^= 1;
+        let sections = parser::active_slot(&raw).expect("Demo save should load");
+        let party = parser::parse_party(&sections, &RADICAL_RED, false)
+            .expect("Failed to parse demo party");
+        assert!(party.iter().all(|p| p.is_traded));
+    }
+
+    #[test]
+    fn test_unencodable_nickname() {
+        let mon = SynthMon {
+            nickname: "ピカチュウ",
+            ..DEMO_PARTY[3]
+        };
+        assert_eq!(build_save(&[mon]), Err(EncodeError::Unencodable('ピ')));
+    }
+}
@@ -0,0 +1,193 @@
+//! Developer tooling: run the whole pipeline on the built-in demo save inside
+//! the packaged app, so an installed build can be checked end to end.
+
+use serde::Serialize;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::corpus::panic_message;
+use crate::error::{AppError, ExportError};
+use crate::facility::{self, FacilityFormat};
+use crate::parser::{self, Pokemon, Section};
+use crate::profile::Game;
+use crate::reader::IoMode;
+use crate::rules::{self, Rule};
+use crate::{pk3, projection, readiness, savegen, trainer};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StepResult {
+    pub step: String,
+    pub passed: bool,
+    /// The error or panic message when the step failed.
+    pub detail: Option<String>,
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SelfTestReport {
+    /// Steps in the order they ran. The run stops at the first failure.
+    pub steps: Vec<StepResult>,
+    pub passed: bool,
+}
+
+impl SelfTestReport {
+    /// Run `step`, recording how it went. Returns its output if it passed.
+    fn run<T>(&mut self, name: &str, step: impl FnOnce() -> Result<T, AppError>) -> Option<T> {
+        let started = Instant::now();
+        let (output, detail) = match panic::catch_unwind(AssertUnwindSafe(step)) {
+            Ok(Ok(output)) => (Some(output), None),
+            Ok(Err(e)) => (None, Some(e.to_string())),
+            Err(payload) => (None, Some(panic_message(payload))),
+        };
+        self.steps.push(StepResult {
+            step: name.to_string(),
+            passed: output.is_some(),
+            detail,
+            duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        });
+        output
+    }
+}
+
+fn check(ok: bool, message: &str) -> Result<(), AppError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(AppError::Other(message.to_string()))
+    }
+}
+
+fn parse(raw: &[u8]) -> Result<(Vec<Section>, Vec<Pokemon>), AppError> {
+    let sections = parser::active_slot(raw)?;
+    check(
+        parser::detect_game(&sections)? == Game::RadicalRed,
+        "Demo save not detected as Radical Red",
+    )?;
+    check(
+        parser::corrupt_sections(&sections, Game::RadicalRed.profile()).is_empty(),
+        "Demo save has corrupt sections",
+    )?;
+    let party = parser::parse_party(&sections, Game::RadicalRed.profile(), false)?;
+    check(!party.is_empty(), "Demo save has no party")?;
+    parser::parse_pc_boxes(&sections, Game::RadicalRed.profile(), false)?;
+    trainer::parse_trainer_info(&sections, Game::RadicalRed.profile())?;
+    Ok((sections, party))
+}
+
+fn analyze(party: &[Pokemon]) -> Result<(), AppError> {
+    readiness::team_readiness(party, Some(100));
+    check(
+        projection::project_stats(party).len() == party.len(),
+        "Stat projections don't cover the party",
+    )?;
+    let rules = [Rule::MinPartySize { size: 7 }];
+    check(
+        rules::evaluate(&rules, party).len() == 1,
+        "Rule evaluation missed an oversized minimum party",
+    )
+}
+
+fn export(sections: &[Section], party: &[Pokemon]) -> Result<(), AppError> {
+    check(
+        party.iter().all(|mon| !mon.display_text.is_empty()),
+        "Empty Showdown text",
+    )?;
+    let json = serde_json::to_string(party).map_err(|e| ExportError::Serialize(e.to_string()))?;
+    serde_json::from_str::<serde_json::Value>(&json)
+        .map_err(|e| ExportError::Serialize(e.to_string()))?;
+
+    let slots: Vec<usize> = (0..party.len().min(3)).collect();
+    let team = facility::facility_team(party, &slots, FacilityFormat::Singles, false)?;
+    check(
+        team.pokemon.len() == slots.len(),
+        "Facility team lost Pokemon",
+    )?;
+
+    // Party members with moves or species vanilla lacks can't be converted;
+    // the rest must come back unchanged.
+    let profile = Game::RadicalRed.profile();
+    let sec1 = parser::find_section(sections, profile.party_section)?;
+    let mut converted = 0;
+    for slot in 0..party.len() {
+        let off = profile.party_offset + slot * profile.pokemon.size;
+        let raw = &sec1[off..off + profile.pokemon.size];
+        if let Ok(vanilla) = pk3::convert(raw, pk3::Format::Vanilla) {
+            check(
+                pk3::convert(&vanilla, pk3::Format::Cfru)? == raw,
+                "pk3 conversion doesn't round-trip",
+            )?;
+            converted += 1;
+        }
+    }
+    check(converted > 0, "No party member converts to vanilla")
+}
+
+fn round_trip(dir: &Path, raw: &[u8], party: &[Pokemon]) -> Result<(), AppError> {
+    fs::create_dir_all(dir).map_err(|e| ExportError::CreateDir(e.to_string()))?;
+    let path = dir.join("self-test.sav");
+    fs::write(&path, raw).map_err(|e| ExportError::Write(e.to_string()))?;
+    let sections = parser::load_active_slot(&path.to_string_lossy(), IoMode::Direct);
+    fs::remove_dir_all(dir).map_err(|e| ExportError::Write(e.to_string()))?;
+    let reread = parser::parse_party(&sections?, Game::RadicalRed.profile(), false)?;
+    let texts = |mons: &[Pokemon]| {
+        mons.iter()
+            .map(|m| m.display_text.clone())
+            .collect::<Vec<_>>()
+    };
+    check(
+        texts(&reread) == texts(party),
+        "Party changed after writing the save to disk",
+    )
+}
+
+/// Build the demo save, parse it, run the analyses and exports over it, then
+/// write it under `temp_dir` and read it back.
+pub fn self_test(temp_dir: &Path) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+    let passed = (|| {
+        let raw = report.run("build_save", || Ok(savegen::demo_save()?))?;
+        let (sections, party) = report.run("parse", || parse(&raw))?;
+        report.run("analyze", || analyze(&party))?;
+        report.run("export", || export(&sections, &party))?;
+        report.run("round_trip", || {
+            round_trip(
+                &temp_dir.join(format!("rr-self-test-{}", std::process::id())),
+                &raw,
+                &party,
+            )
+        })
+    })()
+    .is_some();
+    report.passed = passed;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test_passes() {
+        let report = self_test(&std::env::temp_dir());
+        let steps: Vec<&str> = report.steps.iter().map(|s| s.step.as_str()).collect();
+        assert!(report.passed, "{:?}", report.steps);
+        assert_eq!(
+            steps,
+            ["build_save", "parse", "analyze", "export", "round_trip"]
+        );
+    }
+
+    #[test]
+    fn test_self_test_stops_at_failure() {
+        let mut report = SelfTestReport::default();
+        let failed = report.run("fails", || {
+            Err::<(), _>(AppError::Other("broken".to_string()))
+        });
+        let panicked = report.run("panics", || -> Result<(), AppError> { panic!("boom") });
+        assert!(failed.is_none() && panicked.is_none());
+        assert_eq!(report.steps[0].detail.as_deref(), Some("broken"));
+        assert_eq!(report.steps[1].detail.as_deref(), Some("boom"));
+    }
+}
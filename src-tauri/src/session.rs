@@ -0,0 +1,118 @@
+//! The most recently parsed save, shared with every open window.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::ParseError;
+use crate::parser::{self, CorruptSection, ParseOptions, ParseResult, Pokemon};
+use crate::profile::Game;
+use crate::rules::RuleWarning;
+
+/// Emitted to every window with the new `LoadedSave` whenever a save is parsed.
+pub const SAVE_UPDATED_EVENT: &str = "save-updated";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LoadedSave {
+    /// None for the built-in demo save.
+    pub path: Option<String>,
+    /// The game the save was parsed as, whether configured or detected.
+    pub game: Game,
+    /// Sections that failed their checksum, so the party may be wrong.
+    pub corrupt_sections: Vec<CorruptSection>,
+    pub party: Vec<Pokemon>,
+    /// The user's rules broken by `party`.
+    pub rule_warnings: Vec<RuleWarning>,
+}
+
+/// Kept in Tauri managed state so windows opened later can pick up the current save.
+#[derive(Default)]
+pub struct SaveState(Mutex<Option<LoadedSave>>);
+
+impl SaveState {
+    /// Store `save` as the current save and broadcast it to all windows. The
+    /// save is stored either way, so a failed broadcast is only logged; windows
+    /// can still fetch it with `current`.
+    pub fn publish(&self, app: &AppHandle, save: LoadedSave) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(save.clone());
+        if let Err(e) = app.emit(SAVE_UPDATED_EVENT, save) {
+            eprintln!("Failed to broadcast save: {}", e);
+        }
+    }
+
+    pub fn current(&self) -> Option<LoadedSave> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// The inputs and result of the last parse.
+struct CachedParse {
+    raw: Vec<u8>,
+    game: Option<Game>,
+    options: ParseOptions,
+    clean_paste: bool,
+    result: ParseResult,
+}
+
+/// Kept in Tauri managed state so parsing the same save again with the same
+/// settings, as Copy All does on every click, reuses the last result.
+#[derive(Default)]
+pub struct ParseCache(Mutex<Option<CachedParse>>);
+
+impl ParseCache {
+    /// Parse `raw` as `parser::parse_sav_bytes` would, or reuse the last
+    /// result if the bytes and settings are unchanged. The flag is true for
+    /// a reused result.
+    pub fn parse(
+        &self,
+        raw: Vec<u8>,
+        game: Option<Game>,
+        options: ParseOptions,
+        clean_paste: bool,
+    ) -> Result<(ParseResult, bool), ParseError> {
+        let mut cached = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(hit) = cached.as_ref().filter(|c| {
+            c.raw == raw && c.game == game && c.options == options && c.clean_paste == clean_paste
+        }) {
+            return Ok((hit.result.clone(), true));
+        }
+        let result = parser::parse_sav_bytes(&raw, game, options, clean_paste)?;
+        *cached = Some(CachedParse {
+            raw,
+            game,
+            options,
+            clean_paste,
+            result: result.clone(),
+        });
+        Ok((result, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::savegen;
+
+    #[test]
+    fn test_parse_cache() {
+        let raw = std::fs::read(TEST_SAV).unwrap();
+        let cache = ParseCache::default();
+        let parse = |raw: &[u8], clean_paste| {
+            let options = ParseOptions::default();
+            let (result, hit) = cache
+                .parse(raw.to_vec(), None, options, clean_paste)
+                .unwrap();
+            (result.party.len(), hit)
+        };
+
+        assert_eq!(parse(&raw, false), (6, false));
+        assert_eq!(parse(&raw, false), (6, true));
+        assert_eq!(parse(&raw, true), (6, false));
+        assert_eq!(parse(&savegen::demo_save().unwrap(), true), (6, false));
+        assert!(cache
+            .parse(vec![0; 16], None, ParseOptions::default(), true)
+            .is_err());
+        assert_eq!(parse(&raw, true), (6, false));
+    }
+}
@@ -1,19 +1,44 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::SettingsError;
+use crate::profile::Game;
+use crate::reader::IoMode;
+use crate::rules::Rule;
+
+/// Emitted to every window with the new settings after any setting is written.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct Settings {
     sav_path: Option<String>,
+    #[serde(default)]
+    clean_paste: bool,
+    #[serde(default)]
+    io_mode: IoMode,
+    #[serde(default)]
+    own_catches_only: bool,
+    /// None means detect the game from each save.
+    #[serde(default)]
+    game: Option<Game>,
+    #[serde(default)]
+    rules: Vec<Rule>,
+    /// Folder every parsed party is archived to; None turns archiving off.
+    #[serde(default)]
+    archive_dir: Option<String>,
+    /// Level cap before the first badge, then after each badge.
+    #[serde(default)]
+    level_caps: Vec<u8>,
 }
 
-fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn settings_path(app: &AppHandle) -> Result<PathBuf, SettingsError> {
     let dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data dir: {}", e))?;
+        .map_err(|e| SettingsError::AppDataDir(e.to_string()))?;
+    fs::create_dir_all(&dir).map_err(|e| SettingsError::AppDataDir(e.to_string()))?;
     Ok(dir.join("settings.json"))
 }
 
@@ -28,11 +53,16 @@ fn load_settings(app: &AppHandle) -> Settings {
     }
 }
 
-fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+/// Write the settings and tell every window. The write is what matters, so a
+/// failed broadcast is only logged.
+fn save_settings(app: &AppHandle, settings: &Settings) -> Result<(), SettingsError> {
     let path = settings_path(app)?;
     let json = serde_json::to_string_pretty(settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))?;
+        .map_err(|e| SettingsError::Serialize(e.to_string()))?;
+    fs::write(&path, json).map_err(|e| SettingsError::Write(e.to_string()))?;
+    if let Err(e) = app.emit(SETTINGS_CHANGED_EVENT, settings) {
+        eprintln!("Failed to broadcast settings: {}", e);
+    }
     Ok(())
 }
 
@@ -40,8 +70,82 @@ pub fn get_saved_path(app: &AppHandle) -> Option<String> {
     load_settings(app).sav_path
 }
 
-pub fn set_saved_path(app: &AppHandle, path: &str) -> Result<(), String> {
+/// Remember `path` for next launch. Reloading the same save writes nothing.
+pub fn set_saved_path(app: &AppHandle, path: &str) -> Result<(), SettingsError> {
     let mut settings = load_settings(app);
+    if settings.sav_path.as_deref() == Some(path) {
+        return Ok(());
+    }
     settings.sav_path = Some(path.to_string());
     save_settings(app, &settings)
 }
+
+pub fn get_clean_paste(app: &AppHandle) -> bool {
+    load_settings(app).clean_paste
+}
+
+pub fn set_clean_paste(app: &AppHandle, enabled: bool) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.clean_paste = enabled;
+    save_settings(app, &settings)
+}
+
+pub fn get_io_mode(app: &AppHandle) -> IoMode {
+    load_settings(app).io_mode
+}
+
+pub fn set_io_mode(app: &AppHandle, mode: IoMode) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.io_mode = mode;
+    save_settings(app, &settings)
+}
+
+pub fn get_own_catches_only(app: &AppHandle) -> bool {
+    load_settings(app).own_catches_only
+}
+
+pub fn set_own_catches_only(app: &AppHandle, enabled: bool) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.own_catches_only = enabled;
+    save_settings(app, &settings)
+}
+
+pub fn get_game(app: &AppHandle) -> Option<Game> {
+    load_settings(app).game
+}
+
+pub fn set_game(app: &AppHandle, game: Option<Game>) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.game = game;
+    save_settings(app, &settings)
+}
+
+pub fn get_rules(app: &AppHandle) -> Vec<Rule> {
+    load_settings(app).rules
+}
+
+pub fn set_rules(app: &AppHandle, rules: Vec<Rule>) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.rules = rules;
+    save_settings(app, &settings)
+}
+
+pub fn get_archive_dir(app: &AppHandle) -> Option<String> {
+    load_settings(app).archive_dir
+}
+
+pub fn set_archive_dir(app: &AppHandle, dir: Option<String>) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.archive_dir = dir;
+    save_settings(app, &settings)
+}
+
+pub fn get_level_caps(app: &AppHandle) -> Vec<u8> {
+    load_settings(app).level_caps
+}
+
+pub fn set_level_caps(app: &AppHandle, level_caps: Vec<u8>) -> Result<(), SettingsError> {
+    let mut settings = load_settings(app);
+    settings.level_caps = level_caps;
+    save_settings(app, &settings)
+}
@@ -0,0 +1,143 @@
+//! Backend for the first-run setup wizard: find installed emulators, suggest
+//! save files to pick from, and check the pick before it's remembered.
+
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::ParseError;
+use crate::parser::{self, ParseOptions, Pokemon, SLOT_SIZE};
+use crate::profile::Game;
+use crate::reader::IoMode;
+
+/// Emulator names and the folders they keep in the OS config dir. The case
+/// differs between platforms.
+const EMULATOR_DIRS: [(&str, &str); 5] = [
+    ("mGBA", "mGBA"),
+    ("mGBA", "mgba"),
+    ("RetroArch", "RetroArch"),
+    ("RetroArch", "retroarch"),
+    ("VisualBoyAdvance-M", "visualboyadvance-m"),
+];
+
+/// Save extensions: most emulators write .sav, RetroArch writes .srm.
+const SAVE_EXTENSIONS: [&str; 2] = ["sav", "srm"];
+/// How many folders deep to look below each search root.
+const MAX_SEARCH_DEPTH: usize = 2;
+const MAX_SUGGESTIONS: usize = 20;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Emulator {
+    pub name: String,
+    pub dir: String,
+}
+
+/// Emulators with a config folder in `config_dir`.
+pub fn detect_emulators(config_dir: &Path) -> Vec<Emulator> {
+    let mut found: Vec<Emulator> = Vec::new();
+    for (name, folder) in EMULATOR_DIRS {
+        let dir = config_dir.join(folder);
+        // Case-insensitive filesystems match both spellings of the same folder.
+        if dir.is_dir() && !found.iter().any(|e| e.name == name) {
+            found.push(Emulator {
+                name: name.to_string(),
+                dir: dir.to_string_lossy().into_owned(),
+            });
+        }
+    }
+    found
+}
+
+fn is_save_file(path: &Path) -> bool {
+    let has_save_ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SAVE_EXTENSIONS.iter().any(|s| s.eq_ignore_ascii_case(ext)));
+    // Anything smaller than a save slot can't be a save; 64KiB saves hold just one
+    has_save_ext && fs::metadata(path).is_ok_and(|m| m.len() >= SLOT_SIZE as u64)
+}
+
+fn collect_saves(dir: &Path, depth: usize, out: &mut Vec<(SystemTime, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            if depth < MAX_SEARCH_DEPTH {
+                collect_saves(&path, depth + 1, out);
+            }
+        } else if is_save_file(&path) {
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            out.push((modified, path));
+        }
+    }
+}
+
+/// Save files found under `roots`, most recently modified first.
+pub fn suggest_save_paths(roots: &[PathBuf]) -> Vec<String> {
+    let mut saves = Vec::new();
+    for root in roots {
+        collect_saves(root, 0, &mut saves);
+    }
+    saves.sort_by_key(|(modified, _)| Reverse(*modified));
+
+    let mut paths: Vec<String> = Vec::new();
+    for (_, path) in saves {
+        let path = path.to_string_lossy().into_owned();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths.truncate(MAX_SUGGESTIONS);
+    paths
+}
+
+/// Check that `path` is a save this app can read and return its party as a
+/// preview. Without a configured `game`, it is detected from the save.
+pub fn validate_selection(
+    path: &str,
+    io_mode: IoMode,
+    game: Option<Game>,
+) -> Result<Vec<Pokemon>, ParseError> {
+    let party = parser::parse_sav(path, io_mode, game, ParseOptions::default(), false)?.party;
+    if party.is_empty() {
+        return Err(ParseError::EmptyParty);
+    }
+    Ok(party)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savegen;
+
+    #[test]
+    fn test_setup_flow() {
+        let dir = std::env::temp_dir().join(format!("rr-setup-{}", std::process::id()));
+        let saves = dir.join("mgba").join("saves");
+        fs::create_dir_all(&saves).unwrap();
+        let demo = savegen::demo_save().unwrap();
+        fs::write(saves.join("rr.sav"), &demo).unwrap();
+        fs::write(saves.join("truncated.sav"), &demo[..1000]).unwrap();
+        fs::write(saves.join("notes.txt"), &demo).unwrap();
+
+        let emulators = detect_emulators(&dir);
+        let suggestions = suggest_save_paths(&[PathBuf::from(&emulators[0].dir)]);
+        let blank = dir.join("blank.sav");
+        fs::write(&blank, vec![0u8; demo.len()]).unwrap();
+        let blank_result = validate_selection(blank.to_str().unwrap(), IoMode::Direct, None);
+        let party = validate_selection(&suggestions[0], IoMode::Direct, None);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(emulators.len(), 1);
+        assert_eq!(emulators[0].name, "mGBA");
+        assert_eq!(suggestions.len(), 1);
+        assert!(suggestions[0].ends_with("rr.sav"));
+        assert_eq!(party.expect("Demo save should validate").len(), 6);
+        assert!(blank_result.is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! The trainer card: who the save belongs to, how long they've played and how
+//! far they've got.
+
+use serde::Serialize;
+
+use crate::charmap::decode_gen3_string_in;
+use crate::error::ParseError;
+use crate::parser::{self, bytes_at, read_u32, u16_le, Section};
+use crate::profile::GameProfile;
+
+/// SaveBlock2 offsets, the same in every supported game.
+const NAME_OFFSET: usize = 0x00;
+const GENDER_OFFSET: usize = 0x08;
+const TRAINER_ID_OFFSET: usize = 0x0A;
+const SECRET_ID_OFFSET: usize = 0x0C;
+const PLAY_HOURS_OFFSET: usize = 0x0E;
+const PLAY_MINUTES_OFFSET: usize = 0x10;
+const PLAY_SECONDS_OFFSET: usize = 0x11;
+/// Options: button mode, then text speed (3 bits) and window frame (5 bits),
+/// then sound, battle style and battle scene (1 bit each).
+const BUTTON_MODE_OFFSET: usize = 0x13;
+const TEXT_OPTIONS_OFFSET: usize = 0x14;
+const BATTLE_OPTIONS_OFFSET: usize = 0x15;
+
+const BADGE_COUNT: usize = 8;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrainerGender {
+    Male,
+    Female,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct PlayTime {
+    pub hours: u16,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TextSpeed {
+    Slow,
+    Mid,
+    Fast,
+    /// Hacks add settings past Fast; they're all reported as this.
+    Instant,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonMode {
+    /// "Help" in FRLG, "Normal" in Emerald.
+    Normal,
+    /// L and R switch pages.
+    #[serde(rename = "lr")]
+    LR,
+    /// L acts as A.
+    LEqualsA,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub text_speed: TextSpeed,
+    /// Window frame style, counting from 1 like the options menu.
+    pub window_frame: u8,
+    pub button_mode: ButtonMode,
+    pub stereo: bool,
+    /// True for Set, false for Shift.
+    pub battle_style_set: bool,
+    pub battle_scene: bool,
+}
+
+impl Options {
+    fn from_section(sec0: &[u8]) -> Options {
+        let text = sec0[TEXT_OPTIONS_OFFSET];
+        let battle = sec0[BATTLE_OPTIONS_OFFSET];
+        Options {
+            text_speed: match text & 0x07 {
+                0 => TextSpeed::Slow,
+                1 => TextSpeed::Mid,
+                2 => TextSpeed::Fast,
+                _ => TextSpeed::Instant,
+            },
+            window_frame: (text >> 3) + 1,
+            button_mode: match sec0[BUTTON_MODE_OFFSET] {
+                1 => ButtonMode::LR,
+                2 => ButtonMode::LEqualsA,
+                _ => ButtonMode::Normal,
+            },
+            stereo: battle & 0x01 != 0,
+            battle_style_set: battle & 0x02 != 0,
+            // The game stores whether the battle scene is off
+            battle_scene: battle & 0x04 == 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TrainerInfo {
+    pub name: String,
+    pub gender: TrainerGender,
+    pub trainer_id: u16,
+    pub secret_id: u16,
+    pub play_time: PlayTime,
+    pub money: u32,
+    /// Whether each of the eight gym badges has been earned, in gym order.
+    pub badges: [bool; BADGE_COUNT],
+    pub options: Options,
+}
+
+pub fn parse_trainer_info(
+    sections: &[Section],
+    profile: &GameProfile,
+) -> Result<TrainerInfo, ParseError> {
+    let sec0 = parser::find_section(sections, profile.trainer_section)?;
+    let block = parser::profile_save_block1(sections, profile)?;
+
+    let charset = parser::save_charset(sections, profile)?;
+    let security_key = read_u32(sec0, profile.security_key_offset)?;
+    let mut badges = [false; BADGE_COUNT];
+    for (i, badge) in badges.iter_mut().enumerate() {
+        let flag = profile.badge_flags_start + i;
+        *badge = bytes_at(&block, profile.flags_offset + flag / 8, 1)?[0] & (1 << (flag % 8)) != 0;
+    }
+
+    Ok(TrainerInfo {
+        name: decode_gen3_string_in(&sec0[NAME_OFFSET..NAME_OFFSET + 8], charset),
+        gender: match sec0[GENDER_OFFSET] {
+            0 => TrainerGender::Male,
+            _ => TrainerGender::Female,
+        },
+        trainer_id: u16_le(sec0, TRAINER_ID_OFFSET),
+        secret_id: u16_le(sec0, SECRET_ID_OFFSET),
+        play_time: PlayTime {
+            hours: u16_le(sec0, PLAY_HOURS_OFFSET),
+            minutes: sec0[PLAY_MINUTES_OFFSET],
+            seconds: sec0[PLAY_SECONDS_OFFSET],
+        },
+        money: read_u32(&block, profile.money_offset)? ^ security_key,
+        badges,
+        options: Options::from_section(sec0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_support::TEST_SAV;
+    use crate::profile::RADICAL_RED;
+    use crate::reader::IoMode;
+
+    #[test]
+    fn test_parse_trainer_info() {
+        let sections =
+            parser::load_active_slot(TEST_SAV, IoMode::Direct).expect("Failed to load save");
+        let info = parse_trainer_info(&sections, &RADICAL_RED).expect("Failed to parse trainer");
+        assert_eq!(info.name, "Ppppppp");
+        assert_eq!(info.gender, TrainerGender::Male);
+        assert_eq!((info.trainer_id, info.secret_id), (19242, 53592));
+        assert_eq!(
+            info.play_time,
+            PlayTime {
+                hours: 3,
+                minutes: 17,
+                seconds: 37
+            }
+        );
+        assert_eq!(info.money, 425319);
+        assert_eq!(
+            info.options,
+            Options {
+                text_speed: TextSpeed::Fast,
+                window_frame: 1,
+                button_mode: ButtonMode::LR,
+                stereo: true,
+                battle_style_set: true,
+                battle_scene: true,
+            }
+        );
+        assert_eq!(
+            info.badges,
+            [true, false, false, false, false, false, false, false]
+        );
+    }
+}